@@ -0,0 +1,272 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use syn::spanned::Spanned;
+use syn::{Attribute, Expr, Field, Fields, FnArg, GenericArgument, Item, ItemStruct, Lit, Meta, PathArguments, Type};
+
+use crate::program::{Program, SourceFile};
+use crate::syntax::{derives, render};
+use crate::walk::{self, type_name, FnItem};
+
+// account types whose owner Anchor verifies on deserialization
+const OWNER_CHECKED_ACCOUNTS: [&str; 5] = ["Account", "AccountLoader", "InterfaceAccount", "Program", "Sysvar"];
+
+// A `#[derive(Accounts)]` struct of an Anchor program
+pub struct AccountsStruct<'a> {
+    pub file: &'a SourceFile,
+    pub module_path: Vec<String>,
+    pub item: &'a ItemStruct,
+    pub fields: Vec<AccountField<'a>>,
+}
+
+impl AccountsStruct<'_> {
+    pub fn name(&self) -> String {
+        self.item.ident.to_string()
+    }
+
+    pub fn field(&self, name: &str) -> Option<&AccountField<'_>> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    // Fields that have to sign: `Signer<'info>` or `#[account(signer)]`
+    pub fn signers(&self) -> Vec<&str> {
+        self.fields
+            .iter()
+            .filter(|field| field.is_signer())
+            .map(|field| field.name.as_str())
+            .collect()
+    }
+}
+
+// One account of an Accounts struct with the constraints of its `#[account(..)]` attributes
+pub struct AccountField<'a> {
+    pub name: String,
+    // `Account`, `Signer`, `UncheckedAccount`, .. with any `Box` taken off
+    pub kind: String,
+    // `Vault` for `Account<'info, Vault>`
+    pub inner: Option<String>,
+    pub field: &'a Field,
+    pub constraints: Vec<Constraint>,
+    // the field carries a `/// CHECK:` doc comment
+    pub has_check_doc: bool,
+}
+
+impl AccountField<'_> {
+    pub fn constraint(&self, key: &str) -> Option<&Constraint> {
+        self.constraints.iter().find(|constraint| constraint.key == key)
+    }
+
+    pub fn has(&self, key: &str) -> bool {
+        self.constraint(key).is_some()
+    }
+
+    pub fn is_signer(&self) -> bool {
+        self.kind == "Signer" || self.has("signer")
+    }
+
+    // Anchor compares the owner when it loads typed accounts, `owner = ..` does it for the rest
+    pub fn is_owner_checked(&self) -> bool {
+        OWNER_CHECKED_ACCOUNTS.contains(&self.kind.as_str()) || self.has("owner")
+    }
+
+    pub fn span(&self) -> Span {
+        self.field
+            .ident
+            .as_ref()
+            .map_or_else(|| self.field.span(), |ident| ident.span())
+    }
+}
+
+// One entry of `#[account(..)]`: `mut`, `has_one = authority`, `seeds = [..]`, `token::mint = mint`
+pub struct Constraint {
+    // `::` joined path before the `=`
+    pub key: String,
+    pub value: Option<TokenStream>,
+    pub span: Span,
+}
+
+impl Constraint {
+    // Identifiers used by the value, e.g. `user` and `vault` for `seeds = [b"vault", user.key().as_ref()]`
+    pub fn idents(&self) -> Vec<String> {
+        let mut idents = Vec::new();
+        if let Some(value) = &self.value {
+            collect_idents(value.clone(), &mut idents);
+        }
+        idents
+    }
+
+    // The value as source, e.g. `[b"vault", user.key().as_ref()]`
+    pub fn value_text(&self) -> String {
+        let Some(value) = &self.value else {
+            return String::new();
+        };
+        match syn::parse2::<Expr>(value.clone()) {
+            Ok(expr) => render(&expr),
+            Err(_) => value.to_string(),
+        }
+    }
+}
+
+// The Accounts struct behind a handler's `ctx: Context<Withdraw>` parameter
+pub fn context_struct<'s, 'a>(structs: &'s [AccountsStruct<'a>], func: &FnItem) -> Option<&'s AccountsStruct<'a>> {
+    let name = func.sig.inputs.iter().find_map(|input| match input {
+        FnArg::Typed(pat_type) => context_accounts(&pat_type.ty),
+        FnArg::Receiver(_) => None,
+    })?;
+    structs.iter().find(|accounts| accounts.name() == name)
+}
+
+fn context_accounts(ty: &Type) -> Option<String> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path
+        .path
+        .segments
+        .last()
+        .filter(|segment| segment.ident == "Context")?;
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    arguments.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => type_name(ty),
+        _ => None,
+    })
+}
+
+// Every `#[derive(Accounts)]` struct in the program
+pub fn accounts_structs(program: &Program) -> Vec<AccountsStruct<'_>> {
+    walk::module_items(program)
+        .into_iter()
+        .filter_map(|module_item| match module_item.item {
+            Item::Struct(item_struct) if derives(&item_struct.attrs).iter().any(|name| name == "Accounts") => {
+                Some(AccountsStruct {
+                    file: module_item.file,
+                    module_path: module_item.module_path,
+                    item: item_struct,
+                    fields: account_fields(&item_struct.fields),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn account_fields(fields: &Fields) -> Vec<AccountField<'_>> {
+    let Fields::Named(named) = fields else {
+        return Vec::new();
+    };
+    named
+        .named
+        .iter()
+        .map(|field| {
+            let (kind, inner) = account_type(&field.ty);
+            AccountField {
+                name: field.ident.as_ref().map(|ident| ident.to_string()).unwrap_or_default(),
+                kind,
+                inner,
+                field,
+                constraints: field
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("account"))
+                    .flat_map(parse_constraints)
+                    .collect(),
+                has_check_doc: field.attrs.iter().any(is_check_doc),
+            }
+        })
+        .collect()
+}
+
+// (`Account`, Some(`Vault`)) for `Box<Account<'info, Vault>>`
+fn account_type(ty: &Type) -> (String, Option<String>) {
+    let Type::Path(type_path) = ty else {
+        return (type_name(ty).unwrap_or_default(), None);
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return (String::new(), None);
+    };
+    let generic_types: Vec<&Type> = match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => arguments
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    if segment.ident == "Box" {
+        if let Some(boxed) = generic_types.first() {
+            return account_type(boxed);
+        }
+    }
+    (
+        segment.ident.to_string(),
+        generic_types.last().and_then(|ty| type_name(ty)),
+    )
+}
+
+fn is_check_doc(attr: &Attribute) -> bool {
+    match &attr.meta {
+        Meta::NameValue(name_value) if name_value.path.is_ident("doc") => match &name_value.value {
+            Expr::Lit(expr_lit) => {
+                matches!(&expr_lit.lit, Lit::Str(doc) if doc.value().trim_start().starts_with("CHECK"))
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// Splits the attribute arguments on top level commas. the values are kept as tokens since
+// things like `mut`, `@ ErrorCode::X` and `token::mint = ..` are not valid `Meta`
+fn parse_constraints(attr: &Attribute) -> Vec<Constraint> {
+    let Meta::List(list) = &attr.meta else {
+        return Vec::new();
+    };
+    let mut constraints = Vec::new();
+    let mut current = Vec::new();
+    for token in list.tokens.clone() {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                constraints.extend(constraint(std::mem::take(&mut current)));
+            }
+            _ => current.push(token),
+        }
+    }
+    constraints.extend(constraint(current));
+    constraints
+}
+
+fn constraint(tokens: Vec<TokenTree>) -> Option<Constraint> {
+    let span = tokens.first()?.span();
+    let mut key = Vec::new();
+    let mut rest = tokens.into_iter().peekable();
+    while let Some(token) = rest.peek() {
+        match token {
+            TokenTree::Ident(ident) => key.push(ident.to_string()),
+            TokenTree::Punct(punct) if punct.as_char() == ':' => {}
+            _ => break,
+        }
+        rest.next();
+    }
+    let value = match rest.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => Some(rest.collect()),
+        _ => None,
+    };
+    Some(Constraint {
+        key: key.join("::"),
+        value,
+        span,
+    })
+}
+
+fn collect_idents(tokens: TokenStream, idents: &mut Vec<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => idents.push(ident.to_string()),
+            TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
+    }
+}
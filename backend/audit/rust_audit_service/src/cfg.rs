@@ -0,0 +1,583 @@
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{BinOp, Block, Expr, ExprClosure, Item, Stmt, UnOp};
+
+use crate::syntax::{contains_expr, diverges, expr_diverges, is_panic_macro, position, starts_before, strip};
+
+// every function starts at ENTRY, `return`, `?` and panics lead to EXIT
+pub const ENTRY: usize = 0;
+pub const EXIT: usize = 1;
+
+// A run of expressions that always execute together, in source order
+struct Node<'a> {
+    exprs: Vec<&'a Expr>,
+    preds: Vec<usize>,
+}
+
+// An `if` with one branch that always leaves the function. `pass` is the first node of
+// the other branch, so whatever `pass` dominates only runs once `cond` has let it through
+struct Guard<'a> {
+    cond: &'a Expr,
+    pass: usize,
+}
+
+// A branch of an `if` or the body of a `while`: everything `start` dominates runs with
+// `cond` evaluated to `holds`
+struct Branch<'a> {
+    cond: &'a Expr,
+    start: usize,
+    holds: bool,
+}
+
+// Control flow graph of one function body, with the dominator tree of its nodes
+pub struct Cfg<'a> {
+    guards: Vec<Guard<'a>>,
+    branches: Vec<Branch<'a>>,
+    // nodes the function returns from normally, rather than by `Err(..)`, `?` or a panic
+    returns: Vec<usize>,
+    dominators: Dominators,
+    // extents of the expressions of every node, computed once since spans are costly
+    extents: Vec<Vec<Extent>>,
+}
+
+impl<'a> Cfg<'a> {
+    pub fn new(block: &'a Block) -> Self {
+        let mut builder = Builder {
+            nodes: Vec::new(),
+            guards: Vec::new(),
+            branches: Vec::new(),
+            returns: Vec::new(),
+            loops: Vec::new(),
+        };
+        let entry = builder.node();
+        let exit = builder.node();
+        debug_assert_eq!((entry, exit), (ENTRY, EXIT));
+
+        let body = builder.node();
+        builder.edge(Some(ENTRY), body);
+        let end = builder.block(block, body);
+        builder.edge(end, EXIT);
+        if let Some(end) = end.filter(|_| !diverges(block)) {
+            builder.returns.push(end);
+        }
+
+        let dominators = Dominators::new(&builder.nodes);
+        let extents = builder
+            .nodes
+            .iter()
+            .map(|node| node.exprs.iter().map(|expr| extent(*expr)).collect())
+            .collect();
+        Cfg {
+            guards: builder.guards,
+            branches: builder.branches,
+            returns: builder.returns,
+            dominators,
+            extents,
+        }
+    }
+
+    // The node evaluating `at`, found through the expression of that node which contains it
+    pub fn node_of(&self, at: &impl Spanned) -> Option<usize> {
+        let (start, end) = extent(at);
+        self.extents
+            .iter()
+            .position(|node| node.iter().any(|&(from, to)| from <= start && end <= to))
+    }
+
+    pub fn dominates(&self, a: usize, b: usize) -> bool {
+        self.dominators.dominates(a, b)
+    }
+
+    // True when `at` only runs after `check`: `check` comes first in the same node, or
+    // its node dominates the node of `at`
+    pub fn runs_before(&self, check: &impl Spanned, at: &impl Spanned) -> bool {
+        match (self.node_of(check), self.node_of(at)) {
+            (Some(a), Some(b)) if a == b => starts_before(check, at),
+            (Some(a), Some(b)) => self.dominates(a, b),
+            _ => false,
+        }
+    }
+
+    // Conditions of the `if`s that must let execution through before `at` runs, e.g.
+    // `owner != program_id` for `if owner != program_id { return Err(..) }` ahead of `at`.
+    // checks in a branch that does not return, or after `at` in a loop, are not included
+    pub fn guards_of(&self, at: &impl Spanned) -> Vec<&'a Expr> {
+        let Some(node) = self.node_of(at) else {
+            return Vec::new();
+        };
+        self.guards
+            .iter()
+            .filter(|guard| self.dominates(guard.pass, node))
+            .map(|guard| guard.cond)
+            .collect()
+    }
+
+    // True when every path on which the function returns normally goes through `node`
+    pub fn dominates_returns(&self, node: usize) -> bool {
+        self.returns.iter().all(|&end| self.dominates(node, end))
+    }
+
+    // Every `if` and `while` condition with the value it has in the code `start` dominates
+    pub fn branches(&self) -> impl Iterator<Item = (&'a Expr, bool, usize)> + '_ {
+        self.branches.iter().map(|branch| (branch.cond, branch.holds, branch.start))
+    }
+
+    // Conditions known when `at` runs, with the value they have there: `(a >= b, true)`
+    // inside `if a >= b { .. }`, `(balance < amount, false)` after
+    // `if balance < amount { return Err(..) }` and `(i < n, true)` in `while i < n { .. }`
+    pub fn conditions_of(&self, at: &impl Spanned) -> Vec<(&'a Expr, bool)> {
+        let Some(node) = self.node_of(at) else {
+            return Vec::new();
+        };
+        self.branches
+            .iter()
+            .filter(|branch| self.dominates(branch.start, node))
+            .map(|branch| (branch.cond, branch.holds))
+            .collect()
+    }
+
+    // The parts of `conditions_of(at)` whose value is known when `at` runs, e.g.
+    // `(a.key == b.key, false)` after `if a.key == b.key || amount == 0 { return Err(..) }`
+    pub fn facts_of(&self, at: &impl Spanned) -> Vec<(&'a Expr, bool)> {
+        let mut facts = Vec::new();
+        for (cond, holds) in self.conditions_of(at) {
+            for_each_fact(cond, holds, &mut |fact, holds| facts.push((fact, holds)));
+        }
+        facts
+    }
+}
+
+// Calls `f` on the parts of `cond` whose value is known once `cond` evaluates to `holds`,
+// with that value: both sides of `a && b` holding or of `a || b` failing, `a` failing for `!a`
+pub fn for_each_fact<'e, F: FnMut(&'e Expr, bool)>(cond: &'e Expr, holds: bool, f: &mut F) {
+    f(cond, holds);
+    match cond {
+        Expr::Binary(expr_binary) => {
+            if matches!((&expr_binary.op, holds), (BinOp::And(_), true) | (BinOp::Or(_), false)) {
+                for_each_fact(&expr_binary.left, holds, f);
+                for_each_fact(&expr_binary.right, holds, f);
+            }
+        }
+        Expr::Unary(expr_unary) if matches!(expr_unary.op, UnOp::Not(_)) => for_each_fact(&expr_unary.expr, !holds, f),
+        Expr::Paren(expr_paren) => for_each_fact(&expr_paren.expr, holds, f),
+        _ => {}
+    }
+}
+
+// The two sides of `a == b` or `a != b`, also written `a.eq(&b)` and `a.ne(&b)`, and whether
+// `fact` evaluating to `holds` makes them equal
+pub fn equality(fact: &Expr, holds: bool) -> Option<(&Expr, &Expr, bool)> {
+    match strip(fact) {
+        Expr::Binary(expr_binary) => match expr_binary.op {
+            BinOp::Eq(_) => Some((&expr_binary.left, &expr_binary.right, holds)),
+            BinOp::Ne(_) => Some((&expr_binary.left, &expr_binary.right, !holds)),
+            _ => None,
+        },
+        Expr::MethodCall(method_call) if method_call.args.len() == 1 => match method_call.method.to_string().as_str() {
+            "eq" => Some((&method_call.receiver, &method_call.args[0], holds)),
+            "ne" => Some((&method_call.receiver, &method_call.args[0], !holds)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// `(greater, smaller, strict)` for the comparison `fact` evaluating to `holds`: `(n, i, true)`
+// for `i < n` holding and `(a, b, false)` for `a < b` failing
+pub fn ordering(fact: &Expr, holds: bool) -> Option<(&Expr, &Expr, bool)> {
+    let Expr::Binary(expr_binary) = strip(fact) else {
+        return None;
+    };
+    let (left, right) = (&*expr_binary.left, &*expr_binary.right);
+    match (&expr_binary.op, holds) {
+        (BinOp::Lt(_), true) => Some((right, left, true)),
+        (BinOp::Le(_), true) => Some((right, left, false)),
+        (BinOp::Gt(_), true) => Some((left, right, true)),
+        (BinOp::Ge(_), true) => Some((left, right, false)),
+        (BinOp::Lt(_), false) => Some((left, right, false)),
+        (BinOp::Le(_), false) => Some((left, right, true)),
+        (BinOp::Gt(_), false) => Some((right, left, false)),
+        (BinOp::Ge(_), false) => Some((right, left, true)),
+        _ => None,
+    }
+}
+
+// start and end (line, column) of a span
+type Extent = ((usize, usize), (usize, usize));
+
+fn extent(at: &impl Spanned) -> Extent {
+    let span = at.span();
+    (position(span), (span.end().line, span.end().column))
+}
+
+struct Builder<'a> {
+    nodes: Vec<Node<'a>>,
+    guards: Vec<Guard<'a>>,
+    branches: Vec<Branch<'a>>,
+    returns: Vec<usize>,
+    // (continue target, break target) of the enclosing loops, innermost last
+    loops: Vec<(usize, usize)>,
+}
+
+impl<'a> Builder<'a> {
+    fn node(&mut self) -> usize {
+        self.nodes.push(Node {
+            exprs: Vec::new(),
+            preds: Vec::new(),
+        });
+        self.nodes.len() - 1
+    }
+
+    fn edge(&mut self, from: Option<usize>, to: usize) {
+        if let Some(from) = from {
+            self.nodes[to].preds.push(from);
+        }
+    }
+
+    // `None` once every path through the block has left it by `return`, `break` and the like
+    fn block(&mut self, block: &'a Block, start: usize) -> Option<usize> {
+        let mut current = Some(start);
+        for stmt in &block.stmts {
+            // statements after a `return` still get a node so checks and sinks in them can be found
+            let node = match current {
+                Some(node) => node,
+                None => self.node(),
+            };
+            current = self.stmt(stmt, node);
+        }
+        current
+    }
+
+    fn stmt(&mut self, stmt: &'a Stmt, current: usize) -> Option<usize> {
+        match stmt {
+            Stmt::Local(local) => {
+                let Some(init) = &local.init else {
+                    return Some(current);
+                };
+                let current = self.expr(&init.expr, current)?;
+                // `let Some(x) = .. else { return Err(..) }`
+                if let Some((_, diverge)) = &init.diverge {
+                    let otherwise = self.node();
+                    self.edge(Some(current), otherwise);
+                    let end = self.expr(diverge, otherwise);
+                    self.edge(end, EXIT);
+                    let next = self.node();
+                    self.edge(Some(current), next);
+                    return Some(next);
+                }
+                Some(current)
+            }
+            Stmt::Expr(expr, _) => self.expr(expr, current),
+            Stmt::Macro(stmt_macro) if is_panic_macro(&stmt_macro.mac) => {
+                self.edge(Some(current), EXIT);
+                None
+            }
+            Stmt::Macro(_) | Stmt::Item(_) => Some(current),
+        }
+    }
+
+    fn expr(&mut self, expr: &'a Expr, current: usize) -> Option<usize> {
+        match expr {
+            Expr::If(expr_if) => {
+                let cond = self.expr(&expr_if.cond, current)?;
+                let join = self.node();
+
+                let then_start = self.node();
+                self.edge(Some(cond), then_start);
+                let then_end = self.block(&expr_if.then_branch, then_start);
+                self.edge(then_end, join);
+
+                let else_start = match &expr_if.else_branch {
+                    Some((_, else_expr)) => {
+                        let else_start = self.node();
+                        self.edge(Some(cond), else_start);
+                        let else_end = self.expr(else_expr, else_start);
+                        self.edge(else_end, join);
+                        else_start
+                    }
+                    None => {
+                        self.edge(Some(cond), join);
+                        join
+                    }
+                };
+
+                self.branches.push(Branch {
+                    cond: &expr_if.cond,
+                    start: then_start,
+                    holds: true,
+                });
+                // without an `else` the join is only the failing branch when `then` leaves
+                if expr_if.else_branch.is_some() || diverges(&expr_if.then_branch) {
+                    self.branches.push(Branch {
+                        cond: &expr_if.cond,
+                        start: else_start,
+                        holds: false,
+                    });
+                }
+
+                let else_diverges = expr_if
+                    .else_branch
+                    .as_ref()
+                    .is_some_and(|(_, else_expr)| expr_diverges(else_expr));
+                if diverges(&expr_if.then_branch) {
+                    self.guards.push(Guard {
+                        cond: &expr_if.cond,
+                        pass: else_start,
+                    });
+                } else if else_diverges {
+                    self.guards.push(Guard {
+                        cond: &expr_if.cond,
+                        pass: then_start,
+                    });
+                }
+                self.reachable(join)
+            }
+            Expr::Match(expr_match) => {
+                let scrutinee = self.expr(&expr_match.expr, current)?;
+                let join = self.node();
+                for arm in &expr_match.arms {
+                    let start = self.node();
+                    self.edge(Some(scrutinee), start);
+                    if let Some((_, guard)) = &arm.guard {
+                        self.nodes[start].exprs.push(guard);
+                    }
+                    let end = self.expr(&arm.body, start);
+                    self.edge(end, join);
+                }
+                self.reachable(join)
+            }
+            Expr::Block(expr_block) => self.block(&expr_block.block, current),
+            Expr::Unsafe(expr_unsafe) => self.block(&expr_unsafe.block, current),
+            Expr::Paren(expr_paren) => self.expr(&expr_paren.expr, current),
+            Expr::Group(expr_group) => self.expr(&expr_group.expr, current),
+            Expr::Return(expr_return) => {
+                let current = match &expr_return.expr {
+                    Some(value) => self.expr(value, current)?,
+                    None => current,
+                };
+                self.edge(Some(current), EXIT);
+                if !expr_return.expr.as_deref().is_some_and(expr_diverges) {
+                    self.returns.push(current);
+                }
+                None
+            }
+            Expr::Break(expr_break) => {
+                let current = match &expr_break.expr {
+                    Some(value) => self.expr(value, current)?,
+                    None => current,
+                };
+                if let Some(&(_, after)) = self.loops.last() {
+                    self.edge(Some(current), after);
+                }
+                None
+            }
+            Expr::Continue(_) => {
+                if let Some(&(header, _)) = self.loops.last() {
+                    self.edge(Some(current), header);
+                }
+                None
+            }
+            Expr::Loop(expr_loop) => {
+                let header = self.node();
+                self.edge(Some(current), header);
+                let after = self.node();
+                self.loops.push((header, after));
+                let end = self.block(&expr_loop.body, header);
+                self.edge(end, header);
+                self.loops.pop();
+                self.reachable(after)
+            }
+            Expr::While(expr_while) => {
+                let header = self.node();
+                self.edge(Some(current), header);
+                let cond = self.expr(&expr_while.cond, header).unwrap_or(header);
+                let after = self.node();
+                self.edge(Some(cond), after);
+                let body = self.node();
+                self.edge(Some(cond), body);
+                self.branches.push(Branch {
+                    cond: &expr_while.cond,
+                    start: body,
+                    holds: true,
+                });
+                self.loops.push((header, after));
+                let end = self.block(&expr_while.body, body);
+                self.edge(end, header);
+                self.loops.pop();
+                Some(after)
+            }
+            Expr::ForLoop(expr_for) => {
+                let current = self.expr(&expr_for.expr, current)?;
+                let header = self.node();
+                self.edge(Some(current), header);
+                let after = self.node();
+                self.edge(Some(header), after);
+                let body = self.node();
+                self.edge(Some(header), body);
+                self.loops.push((header, after));
+                let end = self.block(&expr_for.body, body);
+                self.edge(end, header);
+                self.loops.pop();
+                Some(after)
+            }
+            Expr::Macro(expr_macro) if is_panic_macro(&expr_macro.mac) => {
+                self.nodes[current].exprs.push(expr);
+                self.edge(Some(current), EXIT);
+                None
+            }
+            // anything else runs in one go. a `?` or `return` inside it may leave the
+            // function, so what follows starts a node of its own
+            _ => {
+                self.nodes[current].exprs.push(expr);
+                if !may_exit(expr) {
+                    return Some(current);
+                }
+                self.edge(Some(current), EXIT);
+                if returns_normally(expr) {
+                    self.returns.push(current);
+                }
+                let next = self.node();
+                self.edge(Some(current), next);
+                Some(next)
+            }
+        }
+    }
+
+    fn reachable(&self, node: usize) -> Option<usize> {
+        (!self.nodes[node].preds.is_empty()).then_some(node)
+    }
+}
+
+// A `?` or `return` somewhere in `expr`, closures and nested items aside
+fn may_exit(expr: &Expr) -> bool {
+    struct ExitFinder {
+        found: bool,
+    }
+
+    impl<'a> Visit<'a> for ExitFinder {
+        fn visit_expr(&mut self, expr: &'a Expr) {
+            if matches!(expr, Expr::Try(_) | Expr::Return(_)) {
+                self.found = true;
+            }
+            visit::visit_expr(self, expr);
+        }
+
+        fn visit_expr_closure(&mut self, _: &'a ExprClosure) {}
+
+        fn visit_item(&mut self, _: &'a Item) {}
+    }
+
+    let mut finder = ExitFinder { found: false };
+    finder.visit_expr(expr);
+    finder.found
+}
+
+// A `return` of anything but `Err(..)` somewhere in `expr`, closures aside
+fn returns_normally(expr: &Expr) -> bool {
+    contains_expr(expr, |inner| match inner {
+        Expr::Return(expr_return) => !expr_return.expr.as_deref().is_some_and(expr_diverges),
+        _ => false,
+    })
+}
+
+// Dominator tree, built with the iterative algorithm of Cooper, Harvey and Kennedy and
+// numbered depth first, so that `a` dominates `b` when `b` falls in the subtree of `a`
+struct Dominators {
+    pre: Vec<usize>,
+    post: Vec<usize>,
+    reachable: Vec<bool>,
+}
+
+impl Dominators {
+    fn new(nodes: &[Node]) -> Self {
+        let count = nodes.len();
+        let mut succs = vec![Vec::new(); count];
+        for (node, data) in nodes.iter().enumerate() {
+            for &pred in &data.preds {
+                succs[pred].push(node);
+            }
+        }
+
+        // postorder of the nodes reachable from ENTRY
+        let mut order = Vec::with_capacity(count);
+        let mut reachable = vec![false; count];
+        let mut stack = vec![(ENTRY, 0)];
+        reachable[ENTRY] = true;
+        while let Some((node, next)) = stack.pop() {
+            if let Some(&succ) = succs[node].get(next) {
+                stack.push((node, next + 1));
+                if !reachable[succ] {
+                    reachable[succ] = true;
+                    stack.push((succ, 0));
+                }
+            } else {
+                order.push(node);
+            }
+        }
+        let mut rank = vec![0; count];
+        for (index, &node) in order.iter().enumerate() {
+            rank[node] = index;
+        }
+
+        let mut idom: Vec<Option<usize>> = vec![None; count];
+        idom[ENTRY] = Some(ENTRY);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &node in order.iter().rev().filter(|&&node| node != ENTRY) {
+                let mut preds = nodes[node].preds.iter().copied().filter(|&pred| idom[pred].is_some());
+                let Some(first) = preds.next() else {
+                    continue;
+                };
+                let next = preds.fold(first, |mut a, mut b| {
+                    while a != b {
+                        while rank[a] < rank[b] {
+                            a = idom[a].unwrap_or(ENTRY);
+                        }
+                        while rank[b] < rank[a] {
+                            b = idom[b].unwrap_or(ENTRY);
+                        }
+                    }
+                    a
+                });
+                if idom[node] != Some(next) {
+                    idom[node] = Some(next);
+                    changed = true;
+                }
+            }
+        }
+
+        let mut children = vec![Vec::new(); count];
+        for (node, parent) in idom.iter().enumerate() {
+            if let Some(parent) = parent.filter(|&parent| parent != node) {
+                children[parent].push(node);
+            }
+        }
+        let (mut pre, mut post) = (vec![0; count], vec![0; count]);
+        let mut clock = 0;
+        let mut stack = vec![(ENTRY, 0)];
+        while let Some((node, next)) = stack.pop() {
+            if next == 0 {
+                pre[node] = clock;
+                clock += 1;
+            }
+            if let Some(&child) = children[node].get(next) {
+                stack.push((node, next + 1));
+                stack.push((child, 0));
+            } else {
+                post[node] = clock;
+                clock += 1;
+            }
+        }
+
+        Dominators { pre, post, reachable }
+    }
+
+    // nodes nothing reaches are dominated by everything
+    fn dominates(&self, a: usize, b: usize) -> bool {
+        if !self.reachable[b] {
+            return true;
+        }
+        self.reachable[a] && self.pre[a] <= self.pre[b] && self.post[b] <= self.post[a]
+    }
+}
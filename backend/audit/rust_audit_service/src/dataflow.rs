@@ -0,0 +1,274 @@
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Expr, ExprAssign, ExprForLoop, FnArg, Item, Local, Pat, Type};
+
+use crate::syntax::{call_name, is_account_info_type, member_name, path_ident, position, render, strip, strip_pat};
+use crate::walk::FnItem;
+
+// What a binding holds as far as accounts are concerned
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    // one account, named after the parameter or `let` that first produced it, or
+    // `accounts[2]` when it was taken from the account list by a fixed index
+    Account(String),
+    // the account list itself: `accounts`, `&accounts[1..]` or `ctx.remaining_accounts`
+    Accounts(String),
+    // an iterator over the account list, e.g. `&mut accounts.iter()`
+    AccountIter(String),
+}
+
+// One definition of a binding: a parameter, a `let`, a `for` pattern or an assignment
+struct Def {
+    name: String,
+    span: Span,
+    // the definition is only seen by code that starts after this position
+    visible: (usize, usize),
+    value: Option<Value>,
+}
+
+// Def-use information for the bindings of one function. lookups take the last definition
+// that starts before the use, so shadowing and reassignment are followed in source order
+pub struct Dataflow {
+    defs: Vec<Def>,
+}
+
+impl Dataflow {
+    pub fn new(func: &FnItem) -> Self {
+        let mut flow = Dataflow { defs: Vec::new() };
+
+        for input in &func.sig.inputs {
+            let FnArg::Typed(pat_type) = input else {
+                continue;
+            };
+            let Pat::Ident(pat_ident) = strip_pat(&pat_type.pat) else {
+                continue;
+            };
+            let name = pat_ident.ident.to_string();
+            let value = if is_account_info_type(&pat_type.ty) {
+                Some(Value::Account(name.clone()))
+            } else if is_account_list_type(&pat_type.ty) {
+                Some(Value::Accounts(name.clone()))
+            } else {
+                None
+            };
+            flow.defs.push(Def {
+                name,
+                span: pat_ident.ident.span(),
+                visible: (0, 0),
+                value,
+            });
+        }
+
+        Collector { flow: &mut flow }.visit_block(func.block);
+        flow
+    }
+
+    // The account `name` holds where `at` reads it: `vault` for `let v = &vault` followed
+    // by `v.owner`, `accounts[2]` for `let a = &accounts[2]`
+    pub fn account(&self, name: &str, at: &impl Spanned) -> Option<String> {
+        match self.lookup(name, position(at.span()))?.value.as_ref()? {
+            Value::Account(account) => Some(account.clone()),
+            _ => None,
+        }
+    }
+
+    // Like `account`, falling back to the name itself for bindings the function does not
+    // tie to an account, such as the fields of an Anchor `ctx.accounts`
+    pub fn account_key(&self, name: &str, at: &impl Spanned) -> String {
+        self.account(name, at).unwrap_or_else(|| name.to_string())
+    }
+
+    // The account `expr` evaluates to, e.g. `vault` for `&vault`, `vault.clone()` or
+    // `accounts.get(0).unwrap()` with `accounts[0]` bound to `vault` before
+    pub fn account_of(&self, expr: &Expr) -> Option<String> {
+        match self.value_of(expr, "")? {
+            Value::Account(account) if !account.is_empty() => Some(account),
+            _ => None,
+        }
+    }
+
+    // Every name bound to a single account, once, with the span of its first such binding
+    pub fn bindings(&self) -> Vec<(String, Span)> {
+        let mut bindings: Vec<(String, Span)> = Vec::new();
+        for def in &self.defs {
+            if matches!(def.value, Some(Value::Account(_))) && !bindings.iter().any(|(name, _)| *name == def.name) {
+                bindings.push((def.name.clone(), def.span));
+            }
+        }
+        bindings
+    }
+
+    fn lookup(&self, name: &str, at: (usize, usize)) -> Option<&Def> {
+        self.defs
+            .iter()
+            .filter(|def| def.name == name && def.visible <= at)
+            .max_by_key(|def| def.visible)
+    }
+
+    // `fresh` names the account when `expr` produces a new one, like `next_account_info(iter)?`
+    fn value_of(&self, expr: &Expr, fresh: &str) -> Option<Value> {
+        let at = position(expr.span());
+        match strip(expr) {
+            Expr::Path(_) => self.lookup(&path_ident(expr)?, at)?.value.clone(),
+            Expr::Field(expr_field) if member_name(&expr_field.member) == "remaining_accounts" => {
+                Some(Value::Accounts("remaining_accounts".to_string()))
+            }
+            Expr::Index(expr_index) => match (self.value_of(&expr_index.expr, fresh)?, strip(&expr_index.index)) {
+                (Value::Accounts(list), Expr::Range(_)) => Some(Value::Accounts(list)),
+                (Value::Accounts(list), index) => Some(Value::Account(format!("{}[{}]", list, render(index)))),
+                _ => None,
+            },
+            inner @ Expr::Call(_) if call_name(inner).as_deref() == Some("next_account_info") => {
+                Some(Value::Account(fresh.to_string()))
+            }
+            Expr::MethodCall(method_call) => {
+                let receiver = self.value_of(&method_call.receiver, fresh)?;
+                let index = || method_call.args.first().map(render).unwrap_or_default();
+                match (method_call.method.to_string().as_str(), receiver) {
+                    ("iter" | "into_iter", Value::Accounts(list)) => Some(Value::AccountIter(list)),
+                    ("get", Value::Accounts(list)) => Some(Value::Account(format!("{}[{}]", list, index()))),
+                    ("first", Value::Accounts(list)) => Some(Value::Account(format!("{}[0]", list))),
+                    ("next", Value::AccountIter(_)) => Some(Value::Account(fresh.to_string())),
+                    ("skip" | "take" | "by_ref", Value::AccountIter(list)) => Some(Value::AccountIter(list)),
+                    (
+                        "clone" | "to_owned" | "as_ref" | "to_account_info" | "unwrap" | "expect" | "ok_or"
+                        | "ok_or_else",
+                        value,
+                    ) => Some(value),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn define(&mut self, name: String, span: Span, visible: (usize, usize), init: Option<&Expr>) {
+        // a second definition of the same name holds a different account unless it aliases one
+        let fresh = if self.defs.iter().any(|def| def.name == name) {
+            format!("{}#{}", name, visible.0)
+        } else {
+            name.clone()
+        };
+        let value = init.and_then(|init| self.value_of(init, &fresh));
+        self.defs.push(Def {
+            name,
+            span,
+            visible,
+            value,
+        });
+    }
+
+    // `let vault = ..`, `let (a, b) = (x, y)` or `let [payer, vault, ..] = accounts`
+    fn bind(&mut self, pat: &Pat, init: Option<&Expr>, visible: (usize, usize)) {
+        match strip_pat(pat) {
+            Pat::Ident(pat_ident) => self.define(pat_ident.ident.to_string(), pat_ident.ident.span(), visible, init),
+            Pat::Tuple(pat_tuple) => {
+                let elems: Vec<Option<&Expr>> = match init.map(strip) {
+                    Some(Expr::Tuple(expr_tuple)) if expr_tuple.elems.len() == pat_tuple.elems.len() => {
+                        expr_tuple.elems.iter().map(Some).collect()
+                    }
+                    _ => vec![None; pat_tuple.elems.len()],
+                };
+                for (elem, init) in pat_tuple.elems.iter().zip(elems) {
+                    self.bind(elem, init, visible);
+                }
+            }
+            Pat::Slice(pat_slice) => {
+                let list = match init.and_then(|init| self.value_of(init, "")) {
+                    Some(Value::Accounts(list)) => Some(list),
+                    _ => None,
+                };
+                let mut fixed = true;
+                for (index, elem) in pat_slice.elems.iter().enumerate() {
+                    // positions after `..` depend on the length of the list
+                    if matches!(elem, Pat::Rest(_)) {
+                        fixed = false;
+                        continue;
+                    }
+                    let account = list
+                        .as_ref()
+                        .filter(|_| fixed)
+                        .map(|list| Value::Account(format!("{}[{}]", list, index)));
+                    self.bind_value(elem, account, visible);
+                }
+            }
+            pat => self.bind_value(pat, None, visible),
+        }
+    }
+
+    // Binds every name in `pat` to `value`, e.g. the loop variable of `for acc in accounts.iter()`
+    fn bind_value(&mut self, pat: &Pat, value: Option<Value>, visible: (usize, usize)) {
+        struct Names<'p>(Vec<&'p syn::Ident>);
+
+        impl<'p> Visit<'p> for Names<'p> {
+            fn visit_pat_ident(&mut self, pat_ident: &'p syn::PatIdent) {
+                self.0.push(&pat_ident.ident);
+                visit::visit_pat_ident(self, pat_ident);
+            }
+        }
+
+        let mut names = Names(Vec::new());
+        names.visit_pat(pat);
+        for ident in names.0 {
+            self.defs.push(Def {
+                name: ident.to_string(),
+                span: ident.span(),
+                visible,
+                value: value.clone(),
+            });
+        }
+    }
+}
+
+struct Collector<'f> {
+    flow: &'f mut Dataflow,
+}
+
+impl<'a> Visit<'a> for Collector<'_> {
+    fn visit_local(&mut self, local: &'a Local) {
+        visit::visit_local(self, local);
+        let init = local.init.as_ref().map(|init| &*init.expr);
+        self.flow.bind(&local.pat, init, end(local.span()));
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'a ExprAssign) {
+        visit::visit_expr_assign(self, assign);
+        if let (Expr::Path(_), Some(name)) = (&*assign.left, path_ident(&assign.left)) {
+            self.flow
+                .define(name, assign.left.span(), end(assign.span()), Some(&assign.right));
+        }
+    }
+
+    fn visit_expr_for_loop(&mut self, for_loop: &'a ExprForLoop) {
+        self.visit_expr(&for_loop.expr);
+        // every iteration binds a different account of the list
+        let value = match self.flow.value_of(&for_loop.expr, "") {
+            Some(Value::Accounts(_) | Value::AccountIter(_)) => {
+                let fresh = match strip_pat(&for_loop.pat) {
+                    Pat::Ident(pat_ident) => pat_ident.ident.to_string(),
+                    _ => String::new(),
+                };
+                Some(Value::Account(fresh))
+            }
+            _ => None,
+        };
+        self.flow.bind_value(&for_loop.pat, value, end(for_loop.pat.span()));
+        self.visit_block(&for_loop.body);
+    }
+
+    fn visit_item(&mut self, _item: &'a Item) {}
+}
+
+fn end(span: Span) -> (usize, usize) {
+    (span.end().line, span.end().column)
+}
+
+// `&[AccountInfo]` or `&[AccountInfo<'info>]`
+fn is_account_list_type(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => is_account_list_type(&reference.elem),
+        Type::Slice(slice) => is_account_info_type(&slice.elem),
+        _ => false,
+    }
+}
@@ -0,0 +1,537 @@
+use std::collections::HashSet;
+use syn::spanned::Spanned;
+use syn::{BinOp, Block, Expr, Pat, Stmt};
+
+use super::{AnalysisContext, Detector, Sink};
+use crate::finding::{Confidence, Finding, Severity};
+use crate::cfg::Cfg;
+use crate::evidence::Property;
+use crate::syntax::{encloses, for_each_expr, guard_conditions, member_name};
+use crate::walk::FnItem;
+
+const ID: &str = "missing-access-control";
+const SEVERITY: Severity = Severity::High;
+
+pub struct AccessControlDetector;
+
+impl Detector for AccessControlDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Account state written without a preceding signer, authority or owner check."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let patterns = AccessControlPatterns::default();
+
+        ctx.functions
+            .iter()
+            .filter(|func| writes_state(ctx, func) && !has_access_control_checks(ctx, func, &patterns))
+            .map(|func| {
+                func.finding(
+                    ID,
+                    SEVERITY,
+                    func.sig.span(),
+                    format!("{} '{}' may lack access control.", func.kind_label(), func.name()),
+                )
+                .with_confidence(Confidence::Low)
+                .with_remediation(
+                    "Verify the signer or authority (e.g. `is_signer` and a comparison against the stored authority) before writing account state.",
+                )
+            })
+            .collect()
+    }
+}
+
+struct AccessControlPatterns {
+    functions: Vec<String>,
+    methods: Vec<String>,
+    identifiers: Vec<String>,
+}
+
+impl Default for AccessControlPatterns {
+    fn default() -> Self {
+        AccessControlPatterns {
+            functions: vec![
+                "assert_eq".to_string(),
+                "assert_ne".to_string(),
+                "require".to_string(),
+                "assert".to_string(),
+                "require_keys_unequal".to_string(),
+                "require_signer".to_string(),
+                "check_authority".to_string(),
+            ],
+            methods: vec![
+                "is_signer".to_string(),
+                "has_role".to_string(),
+                "has_signer".to_string(),
+                "is_authorized".to_string(),
+            ],
+            identifiers: vec![
+                "owner".to_string(),
+                "authority".to_string(),
+                "admin".to_string(),
+            ],
+        }
+    }
+}
+
+fn expr_modifies_state(expr: &Expr, state_variables: &HashSet<String>) -> bool {
+    match expr {
+        Expr::Assign(expr_assign) => {
+            expr_is_state_variable(&expr_assign.left, state_variables)
+        }
+        Expr::MethodCall(method_call) => {
+            let method_name = method_call.method.to_string();
+            if is_serialize_method(&method_name) {
+                let receiver = &method_call.receiver;
+                if expr_is_state_variable(receiver, state_variables) {
+                    return true;
+                }
+            }
+            method_call
+                .args
+                .iter()
+                .any(|arg| expr_modifies_state(arg, state_variables))
+        }
+        Expr::Block(expr_block) => expr_block
+            .block
+            .stmts
+            .iter()
+            .any(|stmt| stmt_modifies_state(stmt, state_variables)),
+        Expr::If(expr_if) => {
+            expr_modifies_state(&expr_if.cond, state_variables)
+                || expr_if
+                    .then_branch
+                    .stmts
+                    .iter()
+                    .any(|stmt| stmt_modifies_state(stmt, state_variables))
+                || expr_if
+                    .else_branch
+                    .as_ref()
+                    .is_some_and(|(_, else_expr)| {
+                        expr_modifies_state(else_expr, state_variables)
+                    })
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn is_serialize_method(method_name: &str) -> bool {
+    method_name == "serialize" || method_name == "try_to_vec"
+}
+
+pub(crate) trait HasBlock {
+    fn block(&self) -> &syn::Block;
+}
+
+impl HasBlock for FnItem<'_> {
+    fn block(&self) -> &syn::Block {
+        self.block
+    }
+}
+
+pub(crate) fn modifies_state(func: &impl HasBlock) -> bool {
+    let mut state_variables = HashSet::new();
+    for stmt in &func.block().stmts {
+        collect_state_variables_stmt(stmt, &mut state_variables);
+    }
+
+    func.block()
+        .stmts
+        .iter()
+        .any(|stmt| stmt_modifies_state(stmt, &state_variables))
+}
+
+pub(crate) fn collect_state_variables_stmt(stmt: &Stmt, state_variables: &mut HashSet<String>) {
+    match stmt {
+        Stmt::Local(local) => {
+            if let Some(init) = &local.init {
+                if let Pat::Ident(pat_ident) = &local.pat {
+                    let mut expr = &*init.expr;
+                    while let Expr::Try(expr_try) = expr {
+                        expr = &*expr_try.expr;
+                    }
+                    if expr_is_deserialization_of_account_data(expr) {
+                        state_variables.insert(pat_ident.ident.to_string());
+                    }
+                }
+            }
+        }
+        Stmt::Expr(expr, _) => {
+            collect_state_variables_expr(expr, state_variables);
+        }
+        _ => {}
+    }
+}
+
+fn collect_state_variables_expr(expr: &Expr, state_variables: &mut HashSet<String>) {
+    match expr {
+        Expr::Block(expr_block) => {
+            for stmt in &expr_block.block.stmts {
+                collect_state_variables_stmt(stmt, state_variables);
+            }
+        }
+        Expr::If(expr_if) => {
+            collect_state_variables_expr(&expr_if.cond, state_variables);
+            for stmt in &expr_if.then_branch.stmts {
+                collect_state_variables_stmt(stmt, state_variables);
+            }
+            if let Some((_, else_expr)) = &expr_if.else_branch {
+                collect_state_variables_expr(else_expr, state_variables);
+            }
+        }
+        Expr::Match(expr_match) => {
+            collect_state_variables_expr(&expr_match.expr, state_variables);
+            for arm in &expr_match.arms {
+                collect_state_variables_expr(&arm.body, state_variables);
+            }
+        }
+        Expr::Call(_) | Expr::MethodCall(_) if expr_is_deserialization_of_account_data(expr) => {
+            if let Some(ident) = extract_assigned_ident(expr) {
+                state_variables.insert(ident);
+            }
+        }
+        Expr::Assign(expr_assign) => {
+            collect_state_variables_expr(&expr_assign.right, state_variables);
+            if expr_is_deserialization_of_account_data(&expr_assign.right) {
+                if let Some(ident) = extract_ident_from_expr(&expr_assign.left) {
+                    state_variables.insert(ident);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn extract_assigned_ident(expr: &Expr) -> Option<String> {
+    if let Expr::Assign(expr_assign) = expr {
+        extract_ident_from_expr(&expr_assign.left)
+    } else {
+        None
+    }
+}
+
+fn extract_ident_from_expr(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(expr_path) => {
+            expr_path.path.get_ident().map(|ident| ident.to_string())
+        }
+        Expr::Field(expr_field) => {
+            extract_ident_from_expr(&expr_field.base)
+        }
+        _ => None,
+    }
+}
+
+fn expr_is_deserialization_of_account_data(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(call) => {
+            if let Expr::Path(expr_path) = &*call.func {
+                let func_name = expr_path.path.segments.last().unwrap().ident.to_string();
+                let deserialization_methods = ["try_from_slice", "unpack", "deserialize"];
+                if deserialization_methods.contains(&func_name.as_str()) {
+                    if let Some(arg) = call.args.first() {
+                        let mut arg = arg;
+                        if let Expr::Reference(expr_ref) = arg {
+                            arg = &*expr_ref.expr;
+                        }
+                        return expr_is_account_data_borrow(arg);
+                    }
+                }
+            }
+            false
+        }
+        Expr::MethodCall(method_call) => {
+            let method_name = method_call.method.to_string();
+            let deserialization_methods = ["try_from_slice", "unpack", "deserialize"];
+            if deserialization_methods.contains(&method_name.as_str()) {
+                let mut arg = &*method_call.receiver;
+                if let Expr::Reference(expr_ref) = arg {
+                    arg = &*expr_ref.expr;
+                }
+                if expr_is_account_data_borrow(arg) {
+                    return true;
+                }
+            }
+            false
+        }
+        Expr::Try(expr_try) => {
+            expr_is_deserialization_of_account_data(&expr_try.expr)
+        }
+        _ => false,
+    }
+}
+
+fn expr_is_account_data_borrow(expr: &Expr) -> bool {
+    account_data_borrow_owner(expr).is_some()
+}
+
+// `vault` for `vault.data.borrow()`, `vault.data.borrow_mut()` or `vault.try_borrow_mut_data()`
+pub(crate) fn account_data_borrow_owner(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::MethodCall(method_call) => {
+            let method_name = method_call.method.to_string();
+            if method_name == "borrow" || method_name == "borrow_mut" {
+                if let Expr::Field(expr_field) = &*method_call.receiver {
+                    if let Expr::Path(expr_path) = &*expr_field.base {
+                        let ident = expr_path.path.segments.last().unwrap().ident.to_string();
+                        let field = member_name(&expr_field.member);
+                        return (field == "data").then_some(ident);
+                    }
+                }
+            }
+            if method_name == "try_borrow_data" || method_name == "try_borrow_mut_data" {
+                if let Expr::Path(expr_path) = &*method_call.receiver {
+                    return expr_path.path.get_ident().map(|ident| ident.to_string());
+                }
+            }
+            None
+        }
+        Expr::Reference(expr_ref) => {
+            account_data_borrow_owner(&expr_ref.expr)
+        }
+        _ => None,
+    }
+}
+
+fn expr_is_state_variable(expr: &Expr, state_variables: &HashSet<String>) -> bool {
+    match expr {
+        Expr::Path(expr_path) => {
+            let ident = expr_path.path.segments.last().unwrap().ident.to_string();
+            state_variables.contains(&ident)
+        }
+        Expr::Field(expr_field) => {
+            if let Expr::Path(expr_path) = &*expr_field.base {
+                let ident = expr_path.path.segments.last().unwrap().ident.to_string();
+                state_variables.contains(&ident)
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+// State is written by the function itself or by a helper it calls, e.g. `save(&vault, data)?`
+fn writes_state(ctx: &AnalysisContext, func: &FnItem) -> bool {
+    let mut through_helper = false;
+    for_each_expr(func.block, |expr| {
+        through_helper |= ctx
+            .call_graph
+            .summary(expr)
+            .is_some_and(|summary| summary.sinks.contains(&Sink::StateWrite));
+    });
+    modifies_state(func) || through_helper
+}
+
+// Every write to account state has to come after an access control check on all the
+// paths leading to it: a guard whose failing branch leaves the function, or a call such
+// as `check_authority(..)?` made before the write. helpers of the program count by their
+// summary, both as checks and as writes
+fn has_access_control_checks(ctx: &AnalysisContext, func: &FnItem, patterns: &AccessControlPatterns) -> bool {
+    let mut state_variables = HashSet::new();
+    for stmt in &func.block.stmts {
+        collect_state_variables_stmt(stmt, &mut state_variables);
+    }
+
+    let cfg = Cfg::new(func.block);
+    let mut conditions = Vec::new();
+    let mut calls = Vec::new();
+    let mut writes = Vec::new();
+    for_each_expr(func.block, |expr| {
+        match expr {
+            Expr::If(expr_if) => conditions.push(&*expr_if.cond),
+            Expr::While(expr_while) => conditions.push(&*expr_while.cond),
+            _ => {}
+        }
+        let summary = ctx.call_graph.summary(expr);
+        let checking_helper = summary.is_some_and(|summary| {
+            summary.access_control || summary.checks.iter().any(|checks| checks.contains(&Property::Signer))
+        });
+        if is_access_control_call(expr, patterns) || checking_helper {
+            calls.push(expr);
+        }
+        let writing_helper = summary.is_some_and(|summary| summary.sinks.contains(&Sink::StateWrite));
+        if is_state_write(expr, &state_variables) || writing_helper {
+            writes.push(expr);
+        }
+    });
+    // a call only checks anything when its result is not just branched on
+    calls.retain(|call| !conditions.iter().any(|cond| encloses(*cond, *call)));
+
+    writes.iter().all(|write| {
+        cfg.guards_of(*write).into_iter().any(|cond| {
+            check_condition_for_access_control(cond, patterns) || check_expr_for_access_control(cond, patterns)
+        }) || calls.iter().any(|call| cfg.runs_before(*call, *write))
+    })
+}
+
+// A helper such as `fn assert_admin(state, signer) -> ProgramResult` that returns an error
+// unless the caller is allowed, so that calling it is a check of its own
+pub(crate) fn performs_access_control(func: &FnItem) -> bool {
+    let patterns = AccessControlPatterns::default();
+    let mut calls_check = false;
+    for_each_expr(func.block, |expr| calls_check |= is_access_control_call(expr, &patterns));
+    calls_check
+        || guard_conditions(func.block).into_iter().any(|cond| {
+            check_condition_for_access_control(cond, &patterns) || check_expr_for_access_control(cond, &patterns)
+        })
+}
+
+fn is_access_control_call(expr: &Expr, patterns: &AccessControlPatterns) -> bool {
+    match expr {
+        Expr::Call(expr_call) => match &*expr_call.func {
+            Expr::Path(expr_path) => expr_path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| patterns.functions.contains(&segment.ident.to_string())),
+            _ => false,
+        },
+        Expr::MethodCall(method_call) => patterns.methods.contains(&method_call.method.to_string()),
+        _ => false,
+    }
+}
+
+// `state.balance = ..`, `state.balance += ..` or `state.serialize(..)`
+fn is_state_write(expr: &Expr, state_variables: &HashSet<String>) -> bool {
+    match expr {
+        Expr::Assign(expr_assign) => expr_is_state_variable(&expr_assign.left, state_variables),
+        Expr::Binary(expr_binary) => {
+            matches!(
+                expr_binary.op,
+                BinOp::AddAssign(_)
+                    | BinOp::SubAssign(_)
+                    | BinOp::MulAssign(_)
+                    | BinOp::DivAssign(_)
+                    | BinOp::RemAssign(_)
+                    | BinOp::BitXorAssign(_)
+                    | BinOp::BitAndAssign(_)
+                    | BinOp::BitOrAssign(_)
+                    | BinOp::ShlAssign(_)
+                    | BinOp::ShrAssign(_)
+            ) && expr_is_state_variable(&expr_binary.left, state_variables)
+        }
+        Expr::MethodCall(method_call) => {
+            is_serialize_method(&method_call.method.to_string())
+                && expr_is_state_variable(&method_call.receiver, state_variables)
+        }
+        _ => false,
+    }
+}
+
+fn check_stmt_for_access_control(stmt: &Stmt, patterns: &AccessControlPatterns) -> bool {
+    match stmt {
+        Stmt::Expr(expr, _) => check_expr_for_access_control(expr, patterns),
+        Stmt::Local(local) => {
+            if let Some(init) = &local.init {
+                check_expr_for_access_control(&init.expr, patterns)
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+fn stmt_modifies_state(stmt: &Stmt, state_variables: &HashSet<String>) -> bool {
+    match stmt {
+        Stmt::Expr(expr, _) => expr_modifies_state(expr, state_variables),
+        Stmt::Local(local) => {
+            if let Some(init) = &local.init {
+                expr_modifies_state(&init.expr, state_variables)
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+fn check_expr_for_access_control(expr: &Expr, patterns: &AccessControlPatterns) -> bool {
+    match expr {
+        Expr::Call(expr_call) => {
+            if let Expr::Path(expr_path) = &*expr_call.func {
+                let func_name = expr_path.path.segments.last().unwrap().ident.to_string();
+                if patterns.functions.contains(&func_name) {
+                    return true;
+                }
+            }
+            expr_call.args.iter().any(|arg| check_expr_for_access_control(arg, patterns))
+        }
+        Expr::MethodCall(method_call) => {
+            let method_name = method_call.method.to_string();
+            if patterns.methods.contains(&method_name) {
+                return true;
+            }
+            check_expr_for_access_control(&method_call.receiver, patterns)
+                || method_call.args.iter().any(|arg| check_expr_for_access_control(arg, patterns))
+        }
+        Expr::Field(expr_field) => {
+            let field_name = member_name(&expr_field.member);
+            if patterns.identifiers.contains(&field_name) {
+                return true;
+            }
+            check_expr_for_access_control(&expr_field.base, patterns)
+        }
+        Expr::If(expr_if) => {
+            if check_condition_for_access_control(&expr_if.cond, patterns) {
+                return true;
+            }
+            check_block_for_access_control(&expr_if.then_branch, patterns)
+                || expr_if
+                    .else_branch
+                    .as_ref()
+                    .is_some_and(|(_, else_expr)| {
+                        check_expr_for_access_control(else_expr, patterns)
+                    })
+        }
+        Expr::Binary(expr_binary) => {
+            if check_condition_for_access_control(expr, patterns) {
+                return true;
+            }
+            check_expr_for_access_control(&expr_binary.left, patterns)
+                || check_expr_for_access_control(&expr_binary.right, patterns)
+        }
+        Expr::Unary(expr_unary) => check_expr_for_access_control(&expr_unary.expr, patterns),
+        Expr::Paren(expr_paren) => check_expr_for_access_control(&expr_paren.expr, patterns),
+        _ => false,
+    }
+}
+
+fn check_condition_for_access_control(cond: &Expr, patterns: &AccessControlPatterns) -> bool {
+    match cond {
+        Expr::Binary(expr_binary) => {
+            check_expr_for_access_control(&expr_binary.left, patterns)
+                || check_expr_for_access_control(&expr_binary.right, patterns)
+        }
+        Expr::Unary(expr_unary) => {
+            check_expr_for_access_control(&expr_unary.expr, patterns)
+        }
+        Expr::MethodCall(method_call) => {
+            let method_name = method_call.method.to_string();
+            patterns.methods.contains(&method_name)
+        }
+        Expr::Path(expr_path) => {
+            let ident = expr_path.path.segments.last().unwrap().ident.to_string();
+            patterns.identifiers.contains(&ident)
+        }
+        _ => false,
+    }
+}
+
+fn check_block_for_access_control(block: &Block, patterns: &AccessControlPatterns) -> bool {
+    for stmt in &block.stmts {
+        if check_stmt_for_access_control(stmt, patterns) {
+            return true;
+        }
+    }
+    false
+}
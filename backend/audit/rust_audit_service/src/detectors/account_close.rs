@@ -0,0 +1,241 @@
+use syn::spanned::Spanned;
+use syn::{BinOp, Expr, Lit};
+
+use super::access_control::{account_data_borrow_owner, is_serialize_method};
+use super::ownership::is_deserialization_call;
+use super::rent::is_returned_to_system_program;
+use super::token_account::unpacked_from;
+use super::{AnalysisContext, Detector};
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{
+    call_args, call_name, contains_expr, for_each_expr, local_init, member_name, path_ident, root_ident, strip,
+};
+use crate::walk::FnItem;
+
+const ID: &str = "unsafe-account-close";
+const SEVERITY: Severity = Severity::High;
+
+pub struct AccountCloseDetector;
+
+impl Detector for AccountCloseDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Account closed by draining its lamports without zeroing its data, marking it closed or handing it back to the system program."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions.iter().flat_map(check_account_close).collect()
+    }
+}
+
+fn check_account_close(func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let mut drains: Vec<(String, &Expr)> = Vec::new();
+
+    for_each_expr(func.block, |expr| {
+        if let Some(binding) = drained_account(expr) {
+            if !drains.iter().any(|(drained, _)| *drained == binding) {
+                drains.push((binding, expr));
+            }
+        }
+    });
+
+    for (binding, drain) in drains {
+        if is_safely_closed(func, &binding) {
+            continue;
+        }
+
+        issues.push(
+            func.finding(
+                ID,
+                SEVERITY,
+                drain.span(),
+                format!(
+                    "{} '{}' closes account '{}' by draining its lamports but leaves its data and owner in place, so it can be revived within the same transaction.",
+                    func.kind_label(),
+                    func.name(),
+                    binding
+                ),
+            )
+            .with_confidence(Confidence::Medium)
+            .with_remediation(&format!(
+                "Zero `{0}`'s data (or write a closed-account discriminator) and `{0}.assign(&system_program::ID)` after moving its lamports out.",
+                binding
+            )),
+        );
+    }
+
+    issues
+}
+
+// `vault` for `**vault.lamports.borrow_mut() = 0`, `**vault.try_borrow_mut_lamports()? = 0`
+// or `**vault.lamports.borrow_mut() -= vault.lamports()`
+fn drained_account(expr: &Expr) -> Option<String> {
+    let (target, value) = match expr {
+        Expr::Assign(expr_assign) => {
+            if !is_zero(&expr_assign.right) {
+                return None;
+            }
+            (&*expr_assign.left, None)
+        }
+        Expr::Binary(expr_binary) if matches!(expr_binary.op, BinOp::SubAssign(_)) => {
+            (&*expr_binary.left, Some(&*expr_binary.right))
+        }
+        _ => return None,
+    };
+
+    let binding = lamports_borrow_owner(strip(target))?;
+    match value {
+        // only a debit of the account's whole balance closes it
+        Some(value) => (lamports_owner(value).as_deref() == Some(binding.as_str())).then_some(binding),
+        None => Some(binding),
+    }
+}
+
+pub(crate) fn lamports_borrow_owner(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::MethodCall(method_call) => match method_call.method.to_string().as_str() {
+            "borrow_mut" => match strip(&method_call.receiver) {
+                Expr::Field(expr_field) if member_name(&expr_field.member) == "lamports" => {
+                    root_ident(&expr_field.base)
+                }
+                _ => None,
+            },
+            "try_borrow_mut_lamports" => root_ident(&method_call.receiver),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// `vault.lamports()` or `**vault.lamports.borrow()`
+pub(crate) fn lamports_owner(expr: &Expr) -> Option<String> {
+    match strip(expr) {
+        Expr::MethodCall(method_call) if method_call.method == "lamports" => root_ident(&method_call.receiver),
+        Expr::MethodCall(method_call) if method_call.method == "borrow" => match strip(&method_call.receiver) {
+            Expr::Field(expr_field) if member_name(&expr_field.member) == "lamports" => root_ident(&expr_field.base),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn is_zero(expr: &Expr) -> bool {
+    match strip(expr) {
+        Expr::Lit(expr_lit) => matches!(&expr_lit.lit, Lit::Int(lit_int) if lit_int.base10_digits() == "0"),
+        _ => false,
+    }
+}
+
+// Any of: the data is zeroed or truncated, a closed discriminator or flag is written,
+// or the account is assigned back to the system program
+fn is_safely_closed(func: &FnItem, binding: &str) -> bool {
+    let borrows_data =
+        |expr: &Expr| contains_expr(expr, |inner| account_data_borrow_owner(inner).as_deref() == Some(binding));
+    let mut closed = false;
+
+    for_each_expr(func.block, |expr| {
+        if closed {
+            return;
+        }
+        closed = match expr {
+            // `vault.data.borrow_mut().fill(0)`, `..copy_from_slice(&CLOSED_ACCOUNT_DISCRIMINATOR)`,
+            // `vault.assign(&system_program::ID)`, `vault.realloc(0, false)`
+            Expr::MethodCall(method_call) => match method_call.method.to_string().as_str() {
+                "fill" => borrows_data(&method_call.receiver),
+                "copy_from_slice" | "clone_from_slice" | "write_all" => {
+                    borrows_data(&method_call.receiver) && method_call.args.iter().any(mentions_closed)
+                }
+                "assign" => {
+                    root_ident(&method_call.receiver).as_deref() == Some(binding)
+                        && is_returned_to_system_program(method_call)
+                }
+                "realloc" => {
+                    root_ident(&method_call.receiver).as_deref() == Some(binding)
+                        && method_call.args.first().is_some_and(is_zero)
+                }
+                _ => false,
+            },
+            // `sol_memset(&mut *vault.data.borrow_mut(), 0, len)`
+            Expr::Call(_) => {
+                call_name(expr).as_deref() == Some("sol_memset")
+                    && call_args(expr).first().is_some_and(|arg| borrows_data(arg))
+            }
+            // `for byte in vault.data.borrow_mut().iter_mut() { *byte = 0; }`
+            Expr::ForLoop(expr_for) => {
+                borrows_data(&expr_for.expr)
+                    && expr_for.body.stmts.iter().any(|stmt| match stmt {
+                        syn::Stmt::Expr(Expr::Assign(expr_assign), _) => is_zero(&expr_assign.right),
+                        _ => false,
+                    })
+            }
+            // `state.is_closed = true`, `state.discriminator = CLOSED_ACCOUNT_DISCRIMINATOR`, where
+            // `state` was read from the drained account and is serialized back into it
+            Expr::Assign(expr_assign) => match strip(&expr_assign.left) {
+                Expr::Field(expr_field) => {
+                    let field = member_name(&expr_field.member).to_lowercase();
+                    let marks_closed =
+                        field.contains("closed") || field.contains("discriminator") || field == "is_initialized";
+                    marks_closed
+                        && path_ident(&expr_field.base).is_some_and(|state| {
+                            is_state_of(func, &state, binding, expr) && is_written_back(func, &state, binding)
+                        })
+                }
+                _ => false,
+            },
+            _ => false,
+        };
+    });
+
+    closed
+}
+
+// `state` was deserialized from `binding`'s data, e.g. `let state = Vault::try_from_slice(&vault.data.borrow())?`
+fn is_state_of(func: &FnItem, state: &str, binding: &str, at: &Expr) -> bool {
+    local_init(func.block, state, at)
+        .filter(|init| contains_expr(init, is_deserialization_call))
+        .and_then(|init| unpacked_from(func, init, at))
+        .as_deref()
+        == Some(binding)
+}
+
+// `state.serialize(&mut &mut vault.data.borrow_mut()[..])` or `Vault::pack(state, &mut vault.data.borrow_mut())`
+fn is_written_back(func: &FnItem, state: &str, binding: &str) -> bool {
+    let mut written = false;
+    for_each_expr(func.block, |expr| {
+        let (value, target) = match expr {
+            Expr::MethodCall(method_call) if is_serialize_method(&method_call.method.to_string()) => {
+                (&*method_call.receiver, method_call.args.first())
+            }
+            Expr::Call(_) if matches!(call_name(expr).as_deref(), Some("pack" | "pack_into_slice")) => {
+                let args = call_args(expr);
+                match args.first() {
+                    Some(value) => (*value, args.get(1).copied()),
+                    None => return,
+                }
+            }
+            _ => return,
+        };
+        written |= root_ident(value).as_deref() == Some(state)
+            && target.and_then(|target| unpacked_from(func, target, expr)).as_deref() == Some(binding);
+    });
+    written
+}
+
+fn mentions_closed(expr: &Expr) -> bool {
+    contains_expr(expr, |inner| match inner {
+        Expr::Path(expr_path) => expr_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident.to_string().to_lowercase().contains("closed")),
+        _ => false,
+    })
+}
@@ -0,0 +1,160 @@
+use proc_macro2::Span;
+
+use super::{AnalysisContext, Detector};
+use crate::anchor::{AccountField, AccountsStruct};
+use crate::finding::{Confidence, Finding, Location, Severity};
+
+const ID: &str = "anchor-account-constraints";
+const SEVERITY: Severity = Severity::High;
+
+// account types that deserialize program owned data and are worth tying to a signer when mutable
+const DATA_ACCOUNTS: [&str; 3] = ["Account", "AccountLoader", "InterfaceAccount"];
+
+// account types Anchor does not check at all
+const UNCHECKED_ACCOUNTS: [&str; 2] = ["UncheckedAccount", "AccountInfo"];
+
+pub struct AnchorConstraintsDetector;
+
+impl Detector for AnchorConstraintsDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Anchor `#[derive(Accounts)]` struct with unchecked accounts, mutable accounts not tied to a signer, `init_if_needed`, seeds without a bump or closes to a non-signer."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.accounts_structs.iter().flat_map(check_accounts_struct).collect()
+    }
+}
+
+fn check_accounts_struct(accounts: &AccountsStruct) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let signers = accounts.signers();
+    let finding = |span: Span, message: String| {
+        Finding::new(ID, SEVERITY, Location::from_span(&accounts.file.path, span), message)
+            .with_module_path(&accounts.module_path)
+    };
+
+    for field in &accounts.fields {
+        if UNCHECKED_ACCOUNTS.contains(&field.kind.as_str()) && !field.has_check_doc {
+            issues.push(
+                finding(
+                    field.span(),
+                    format!(
+                        "Accounts struct '{}' takes '{}' as `{}` without a `/// CHECK:` comment explaining why it needs no owner or type check.",
+                        accounts.name(),
+                        field.name,
+                        field.kind
+                    ),
+                )
+                .with_confidence(Confidence::Medium)
+                .with_remediation(&format!(
+                    "Use a typed account such as `Account<'info, T>` or `Program<'info, T>` for '{}', or document the manual checks in a `/// CHECK:` comment and add constraints for them.",
+                    field.name
+                )),
+            );
+        }
+
+        if field.has("mut")
+            && DATA_ACCOUNTS.contains(&field.kind.as_str())
+            && !field.has("init")
+            && !field.has("init_if_needed")
+            && !is_tied_to_signer(field, &signers)
+        {
+            issues.push(
+                finding(
+                    field.span(),
+                    format!(
+                        "Accounts struct '{}' marks '{}' as `mut` without a `has_one`, `constraint` or `seeds` tying it to a signer, so any {} account can be passed in and modified.",
+                        accounts.name(),
+                        field.name,
+                        field.inner.as_deref().unwrap_or("matching")
+                    ),
+                )
+                .with_confidence(if signers.is_empty() { Confidence::Low } else { Confidence::Medium })
+                .with_remediation(&match signers.first() {
+                    Some(signer) => format!("Add `has_one = {}` or a `constraint` comparing '{}' with the signer.", signer, field.name),
+                    None => format!("Add a `Signer` to the struct and a `has_one` or `constraint` tying '{}' to it.", field.name),
+                }),
+            );
+        }
+
+        if let Some(init_if_needed) = field.constraint("init_if_needed") {
+            issues.push(
+                finding(
+                    init_if_needed.span,
+                    format!(
+                        "Accounts struct '{}' uses `init_if_needed` on '{}', so the handler also runs on an account that already exists and can be reinitialized.",
+                        accounts.name(),
+                        field.name
+                    ),
+                )
+                .with_confidence(Confidence::Medium)
+                .with_remediation("Use `init` in a dedicated instruction, or check in the handler that the account has not been initialized yet."),
+            );
+        }
+
+        if let (Some(seeds), false) = (field.constraint("seeds"), field.has("bump")) {
+            issues.push(
+                finding(
+                    seeds.span,
+                    format!(
+                        "Accounts struct '{}' derives '{}' from seeds {} without a `bump` constraint.",
+                        accounts.name(),
+                        field.name,
+                        seeds.value_text()
+                    ),
+                )
+                .with_confidence(Confidence::High)
+                .with_remediation(
+                    "Add `bump` (or `bump = <account>.bump` with the stored canonical bump) next to `seeds`.",
+                ),
+            );
+        }
+
+        if let Some(close) = field.constraint("close") {
+            let target = close.value_text();
+            let target_is_signer = accounts.field(&target).is_some_and(|target| target.is_signer());
+            let target_is_bound = field.constraints.iter().any(|constraint| {
+                matches!(constraint.key.as_str(), "has_one" | "constraint") && constraint.idents().contains(&target)
+            });
+            if !target_is_signer && !target_is_bound {
+                issues.push(
+                    finding(
+                        close.span,
+                        format!(
+                            "Accounts struct '{}' closes '{}' to '{}', which is not a signer, so the rent can be sent to any account the caller passes in.",
+                            accounts.name(),
+                            field.name,
+                            target
+                        ),
+                    )
+                    .with_confidence(Confidence::Medium)
+                    .with_remediation(&format!(
+                        "Make '{}' a `Signer`, or add `has_one = {}` to '{}' so the rent goes back to the stored recipient.",
+                        target, target, field.name
+                    )),
+                );
+            }
+        }
+    }
+
+    issues
+}
+
+// `has_one = authority` where `authority` signs, or a `constraint` or `seeds` mentioning a signer
+fn is_tied_to_signer(field: &AccountField, signers: &[&str]) -> bool {
+    field.constraints.iter().any(|constraint| {
+        matches!(constraint.key.as_str(), "has_one" | "constraint" | "seeds")
+            && constraint
+                .idents()
+                .iter()
+                .any(|ident| signers.contains(&ident.as_str()))
+    })
+}
@@ -0,0 +1,186 @@
+use syn::spanned::Spanned;
+use syn::{Expr, Member};
+
+use super::{AnalysisContext, Detector};
+use crate::cfg::{equality, Cfg};
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{account_bindings, call_args, call_name, for_each_expr, key_owner, local_init, path_ident, strip};
+use crate::walk::FnItem;
+
+const ID: &str = "arbitrary-cpi";
+const SEVERITY: Severity = Severity::Critical;
+
+pub struct ArbitraryCpiDetector;
+
+impl Detector for ArbitraryCpiDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "`invoke`/`invoke_signed` of an instruction whose program id is an account key never compared against a known program id."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions.iter().flat_map(check_arbitrary_cpi).collect()
+    }
+}
+
+fn check_arbitrary_cpi(func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let bindings: Vec<String> = account_bindings(func).into_iter().map(|(name, _)| name).collect();
+    let cfg = Cfg::new(func.block);
+
+    for_each_expr(func.block, |expr| {
+        if !is_invoke_call(expr) {
+            return;
+        }
+        let Some(instruction) = call_args(expr).first().copied() else {
+            return;
+        };
+        let Some(program_id) = instruction_program_id(func, instruction, expr) else {
+            return;
+        };
+        let Some(binding) = key_owner(resolve_local(func, program_id, expr)) else {
+            return;
+        };
+        if !bindings.contains(&binding) {
+            return;
+        }
+        let has_program_check = cfg
+            .facts_of(expr)
+            .into_iter()
+            .any(|(fact, holds)| pins_program_id(fact, holds, &binding, |_| true));
+        if has_program_check {
+            return;
+        }
+
+        issues.push(
+            func.finding(
+                ID,
+                SEVERITY,
+                expr.span(),
+                format!(
+                    "{} '{}' invokes the program passed in as account '{}' without checking its key against a known program id.",
+                    func.kind_label(),
+                    func.name(),
+                    binding
+                ),
+            )
+            .with_confidence(Confidence::High)
+            .with_remediation(&format!(
+                "Return `ProgramError::IncorrectProgramId` unless `{}.key` equals the expected program, e.g. `spl_token::id()`.",
+                binding
+            )),
+        );
+    });
+
+    issues
+}
+
+fn is_invoke_call(expr: &Expr) -> bool {
+    matches!(expr, Expr::Call(_))
+        && matches!(
+            call_name(expr).as_deref(),
+            Some("invoke" | "invoke_signed" | "invoke_unchecked" | "invoke_signed_unchecked")
+        )
+}
+
+// The `program_id` an instruction is built with, for `Instruction { program_id, .. }` and
+// `Instruction::new_with_bytes(program_id, ..)`, looking through a local `ix` binding.
+// spl and system instruction builders are left out, they reject foreign program ids themselves
+fn instruction_program_id<'a>(func: &FnItem<'a>, expr: &'a Expr, at: &Expr) -> Option<&'a Expr> {
+    match resolve_local(func, expr, at) {
+        Expr::Struct(expr_struct) if is_instruction_path(&expr_struct.path) => expr_struct
+            .fields
+            .iter()
+            .find(|field| matches!(&field.member, Member::Named(ident) if ident == "program_id"))
+            .map(|field| &field.expr),
+        Expr::Call(call) => match &*call.func {
+            Expr::Path(expr_path)
+                if expr_path.path.segments.len() >= 2
+                    && is_instruction_path(&expr_path.path)
+                    && expr_path
+                        .path
+                        .segments
+                        .last()
+                        .is_some_and(|segment| segment.ident.to_string().starts_with("new_with")) =>
+            {
+                call.args.first()
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// `Instruction`, or the type segment of `Instruction::new_with_bytes`
+fn is_instruction_path(path: &syn::Path) -> bool {
+    let segments: Vec<_> = path.segments.iter().collect();
+    match segments.as_slice() {
+        [.., ty] if ty.ident == "Instruction" => true,
+        [.., ty, _] => ty.ident == "Instruction",
+        _ => false,
+    }
+}
+
+// Follows `ix` back to `let ix = ..` when the expression is a plain local
+fn resolve_local<'a>(func: &FnItem<'a>, expr: &'a Expr, at: &Expr) -> &'a Expr {
+    let expr = strip(expr);
+    match path_ident(expr).and_then(|name| local_init(func.block, &name, at)) {
+        Some(init) => strip(init),
+        None => expr,
+    }
+}
+
+// Whether `fact` evaluating to `holds` pins the key of `binding` to a program id whose
+// qualifier `expected` accepts: `*program.key == spl_token::ID` holding,
+// `program.key != &spl_token::id()` failing or `spl_token::check_id(program.key)` holding
+pub(crate) fn pins_program_id(fact: &Expr, holds: bool, binding: &str, expected: impl Fn(&str) -> bool) -> bool {
+    if let Some((left, right, equal)) = equality(fact, holds) {
+        return equal
+            && [(left, right), (right, left)].into_iter().any(|(key, id)| {
+                key_owner(key).as_deref() == Some(binding) && program_id_qualifier(id).is_some_and(|name| expected(&name))
+            });
+    }
+    match strip(fact) {
+        Expr::Call(call) if holds && call.args.len() == 1 => {
+            key_owner(&call.args[0]).as_deref() == Some(binding)
+                && matches!(&*call.func, Expr::Path(expr_path)
+                    if qualifier(&expr_path.path, "check_id").is_some_and(|name| expected(&name)))
+        }
+        _ => false,
+    }
+}
+
+// `spl_token` for `spl_token::ID` or `spl_token::id()`, and the name of a constant such as
+// `TOKEN_PROGRAM_ID`
+fn program_id_qualifier(expr: &Expr) -> Option<String> {
+    match strip(expr) {
+        Expr::Path(expr_path) => {
+            let name = expr_path.path.segments.last()?.ident.to_string();
+            if name.ends_with("_PROGRAM_ID") {
+                return Some(name);
+            }
+            qualifier(&expr_path.path, "ID")
+        }
+        Expr::Call(call) if call.args.is_empty() => match &*call.func {
+            Expr::Path(expr_path) => qualifier(&expr_path.path, "id"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// The segment before a last segment called `name`, e.g. `rent` in `sysvar::rent::check_id`
+fn qualifier(path: &syn::Path, name: &str) -> Option<String> {
+    let mut segments = path.segments.iter().rev();
+    if segments.next()?.ident != name {
+        return None;
+    }
+    segments.next().map(|segment| segment.ident.to_string())
+}
@@ -0,0 +1,314 @@
+use std::collections::HashSet;
+
+use syn::spanned::Spanned;
+use syn::{BinOp, Expr, FnArg, Lit, Pat, Type, UnOp};
+
+use super::account_close::{lamports_borrow_owner, lamports_owner};
+use super::ownership::is_deserialization_call;
+use super::panic::program_roots;
+use super::{AnalysisContext, Detector};
+use crate::cfg::Cfg;
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{
+    contains_expr, for_each_expr, member_name, render, root_ident, same_value, strip, strip_pat, visit_locals,
+};
+use crate::walk::FnItem;
+
+const ID: &str = "unchecked-arithmetic";
+const SEVERITY: Severity = Severity::High;
+
+// name fragments of values that usually hold token amounts or balances
+const AMOUNT_NAMES: [&str; 14] = [
+    "amount",
+    "balance",
+    "lamports",
+    "supply",
+    "total",
+    "shares",
+    "reward",
+    "fee",
+    "price",
+    "deposit",
+    "stake",
+    "debt",
+    "collateral",
+    "liquidity",
+];
+
+const INTEGER_TYPES: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+pub struct UncheckedArithmeticDetector;
+
+impl Detector for UncheckedArithmeticDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "`+`, `-`, `*` or their compound assignments on lamports, account state or amounts without `checked_*`, `saturating_*` or a bound check."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let roots = program_roots(ctx);
+        ctx.functions
+            .iter()
+            .flat_map(|func| check_unchecked_arithmetic(func, roots.contains(&func.name())))
+            .collect()
+    }
+}
+
+// only the integer parameters of a program root come straight from the instruction
+fn check_unchecked_arithmetic(func: &FnItem, is_root: bool) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let state_variables = state_variables(func);
+    let amount_params = if is_root { integer_params(func) } else { HashSet::new() };
+    let cfg = Cfg::new(func.block);
+
+    for_each_expr(func.block, |expr| {
+        let Expr::Binary(expr_binary) = expr else {
+            return;
+        };
+        let Some((op, method)) = unchecked_op(&expr_binary.op) else {
+            return;
+        };
+        let (left, right) = (strip(&expr_binary.left), strip(&expr_binary.right));
+        if (is_literal(left) && is_literal(right)) || is_float(left) || is_float(right) {
+            return;
+        }
+        let Some((value, confidence)) = describe_value(left, &state_variables, &amount_params)
+            .or_else(|| describe_value(right, &state_variables, &amount_params))
+        else {
+            return;
+        };
+        if is_bounded(&cfg, expr, &expr_binary.op, left, right) {
+            return;
+        }
+
+        issues.push(
+            func.finding(
+                ID,
+                SEVERITY,
+                expr.span(),
+                format!(
+                    "{} '{}' computes `{}` with an unchecked `{}` on {}, which wraps silently in release builds.",
+                    func.kind_label(),
+                    func.name(),
+                    render(expr),
+                    op,
+                    value
+                ),
+            )
+            .with_confidence(confidence)
+            .with_remediation(&format!(
+                "Use `checked_{}` and return an error on overflow, or `saturating_{}` where clamping is intended.",
+                method, method
+            )),
+        );
+    });
+
+    issues
+}
+
+// The operator as written and the `checked_*` method that replaces it
+fn unchecked_op(op: &BinOp) -> Option<(&'static str, &'static str)> {
+    match op {
+        BinOp::Add(_) => Some(("+", "add")),
+        BinOp::Sub(_) => Some(("-", "sub")),
+        BinOp::Mul(_) => Some(("*", "mul")),
+        BinOp::AddAssign(_) => Some(("+=", "add")),
+        BinOp::SubAssign(_) => Some(("-=", "sub")),
+        BinOp::MulAssign(_) => Some(("*=", "mul")),
+        _ => None,
+    }
+}
+
+// What makes an operand worth checking, and how sure we are it carries value:
+// lamports, fields of deserialized account state, integer instruction arguments,
+// then anything named like an amount
+fn describe_value(
+    expr: &Expr,
+    state_variables: &HashSet<String>,
+    amount_params: &HashSet<String>,
+) -> Option<(String, Confidence)> {
+    if let Expr::Cast(expr_cast) = expr {
+        // widening to 128 bits before multiplying is the usual fix
+        if is_wide_integer(&expr_cast.ty) {
+            return None;
+        }
+        return describe_value(strip(&expr_cast.expr), state_variables, amount_params);
+    }
+
+    if contains_expr(expr, is_lamports_access) {
+        let owner = root_ident(expr).unwrap_or_default();
+        return Some((format!("the lamports of '{}'", owner), Confidence::Medium));
+    }
+
+    match expr {
+        Expr::Field(expr_field) => {
+            let is_state = root_ident(&expr_field.base).is_some_and(|root| state_variables.contains(&root))
+                || is_anchor_account_field(&expr_field.base);
+            if is_state {
+                return Some((format!("account state '{}'", render(expr)), Confidence::Medium));
+            }
+            is_amount_name(&member_name(&expr_field.member))
+                .then(|| (format!("amount '{}'", render(expr)), Confidence::Low))
+        }
+        Expr::Path(expr_path) => {
+            let ident = expr_path.path.get_ident()?.to_string();
+            if amount_params.contains(&ident) {
+                Some((format!("instruction argument '{}'", ident), Confidence::Medium))
+            } else if is_amount_name(&ident) {
+                Some((format!("amount '{}'", ident), Confidence::Low))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn is_amount_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    AMOUNT_NAMES.iter().any(|fragment| name.contains(fragment))
+}
+
+// `**vault.lamports.borrow()`, `vault.lamports()`
+fn is_lamports_access(expr: &Expr) -> bool {
+    match expr {
+        Expr::Field(expr_field) => member_name(&expr_field.member) == "lamports",
+        Expr::MethodCall(method_call) => method_call.method == "lamports",
+        _ => false,
+    }
+}
+
+// `ctx.accounts.vault`, whose fields are the account's deserialized state
+pub(crate) fn is_anchor_account_field(expr: &Expr) -> bool {
+    match strip(expr) {
+        Expr::Field(expr_field) => {
+            matches!(strip(&expr_field.base), Expr::Field(inner) if member_name(&inner.member) == "accounts")
+        }
+        _ => false,
+    }
+}
+
+// Locals holding deserialized account data, e.g. `let state = Vault::try_from_slice(..)?`
+pub(crate) fn state_variables(func: &FnItem) -> HashSet<String> {
+    let mut state_variables = HashSet::new();
+    visit_locals(func.block, |local| {
+        if let (Pat::Ident(pat_ident), Some(init)) = (strip_pat(&local.pat), &local.init) {
+            if is_deserialization_call(&init.expr) {
+                state_variables.insert(pat_ident.ident.to_string());
+            }
+        }
+    });
+    state_variables
+}
+
+fn integer_params(func: &FnItem) -> HashSet<String> {
+    func.sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(pat_type) => match (strip_pat(&pat_type.pat), &*pat_type.ty) {
+                (Pat::Ident(pat_ident), Type::Path(type_path))
+                    if type_path
+                        .path
+                        .get_ident()
+                        .is_some_and(|ident| INTEGER_TYPES.contains(&ident.to_string().as_str())) =>
+                {
+                    Some(pat_ident.ident.to_string())
+                }
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+fn is_wide_integer(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.is_ident("u128") || type_path.path.is_ident("i128"))
+}
+
+fn is_literal(expr: &Expr) -> bool {
+    matches!(expr, Expr::Lit(_))
+}
+
+fn is_float(expr: &Expr) -> bool {
+    matches!(expr, Expr::Lit(expr_lit) if matches!(expr_lit.lit, Lit::Float(_)))
+}
+
+// A condition known where the operation runs keeps it in range. `-` needs `left >= right`,
+// e.g. after `if balance < amount { return Err(..) }` or inside `if a >= b { a - b }`.
+// `+` and `*` need an upper bound on one operand in terms of the other, as in
+// `if a > u64::MAX - b { return Err(..) }`, or on both operands
+fn is_bounded(cfg: &Cfg, op_expr: &Expr, op: &BinOp, left: &Expr, right: &Expr) -> bool {
+    let mut facts = Vec::new();
+    for (cond, holds) in cfg.conditions_of(op_expr) {
+        at_least(cond, holds, &mut facts);
+    }
+
+    if matches!(op, BinOp::Sub(_) | BinOp::SubAssign(_)) {
+        return facts
+            .iter()
+            .any(|(greater, smaller)| same_operand(greater, left) && same_value(smaller, right));
+    }
+    // lamports are conserved, so crediting no more than some account holds cannot pass the total supply
+    if lamports_account(left).is_some()
+        && facts
+            .iter()
+            .any(|(greater, smaller)| lamports_account(greater).is_some() && same_value(smaller, right))
+    {
+        return true;
+    }
+    let mentions = |expr: &Expr, value: &Expr| contains_expr(expr, |inner| same_value(inner, value));
+    let bounded_by = |operand: &Expr, other: &Expr| {
+        facts
+            .iter()
+            .any(|(greater, smaller)| same_value(smaller, operand) && mentions(greater, other))
+    };
+    let capped = |operand: &Expr| {
+        is_literal(operand)
+            || facts
+                .iter()
+                .any(|(greater, smaller)| same_value(smaller, operand) && !mentions(greater, operand))
+    };
+    bounded_by(left, right) || bounded_by(right, left) || (capped(left) && capped(right))
+}
+
+// Also equates the lamports of one account however they are read, e.g. the checked
+// `vault.lamports()` and the debited `**vault.try_borrow_mut_lamports()?`
+fn same_operand(a: &Expr, b: &Expr) -> bool {
+    same_value(a, b) || lamports_account(a).is_some_and(|owner| lamports_account(b) == Some(owner))
+}
+
+fn lamports_account(expr: &Expr) -> Option<String> {
+    lamports_owner(expr).or_else(|| lamports_borrow_owner(strip(expr)))
+}
+
+// The `(greater, smaller)` pairs `cond` having the value `holds` implies, e.g.
+// `(b, a)` for `a < b` holding and `(a, b)` for it failing
+fn at_least<'a>(cond: &'a Expr, holds: bool, facts: &mut Vec<(&'a Expr, &'a Expr)>) {
+    match cond {
+        Expr::Paren(expr_paren) => at_least(&expr_paren.expr, holds, facts),
+        Expr::Unary(expr_unary) if matches!(expr_unary.op, UnOp::Not(_)) => at_least(&expr_unary.expr, !holds, facts),
+        Expr::Binary(expr_binary) => {
+            let (left, right) = (&*expr_binary.left, &*expr_binary.right);
+            match (&expr_binary.op, holds) {
+                (BinOp::And(_), true) | (BinOp::Or(_), false) => {
+                    at_least(left, holds, facts);
+                    at_least(right, holds, facts);
+                }
+                (BinOp::Lt(_) | BinOp::Le(_), true) | (BinOp::Gt(_) | BinOp::Ge(_), false) => facts.push((right, left)),
+                (BinOp::Gt(_) | BinOp::Ge(_), true) | (BinOp::Lt(_) | BinOp::Le(_), false) => facts.push((left, right)),
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
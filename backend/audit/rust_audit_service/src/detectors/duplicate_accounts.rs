@@ -0,0 +1,176 @@
+use proc_macro2::Span;
+use syn::Expr;
+
+use super::access_control::account_data_borrow_owner;
+use super::ownership::is_deserialization_call;
+use super::type_cosplay::deserialized_type;
+use super::{AnalysisContext, Detector};
+use crate::cfg::{equality, Cfg};
+use crate::dataflow::Dataflow;
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{
+    account_bindings, contains_expr, for_each_expr, key_owner, member_name, root_ident, strip, visit_locals,
+};
+use crate::walk::FnItem;
+
+const ID: &str = "duplicate-mutable-accounts";
+const SEVERITY: Severity = Severity::Medium;
+
+pub struct DuplicateMutableAccountsDetector;
+
+impl Detector for DuplicateMutableAccountsDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Two accounts of the same type written in one handler without checking that their keys differ."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions.iter().flat_map(check_duplicate_accounts).collect()
+    }
+}
+
+// An account binding the handler writes to, the account it holds, the first write to
+// that account and the struct its data is read as
+struct WrittenAccount<'a> {
+    binding: String,
+    account: String,
+    span: Span,
+    first_write: &'a Expr,
+    data_type: Option<String>,
+}
+
+fn check_duplicate_accounts(func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let flow = Dataflow::new(func);
+    let cfg = Cfg::new(func.block);
+    let written = written_accounts(func, &flow);
+
+    for (index, first) in written.iter().enumerate() {
+        for second in &written[index + 1..] {
+            // accounts whose data is never read as a struct could be of any type
+            let Some(data_type) = first.data_type.as_ref().filter(|_| first.data_type == second.data_type) else {
+                continue;
+            };
+            // the keys must be known to differ before either account is written
+            let checked = [first.first_write, second.first_write].into_iter().all(|write| {
+                cfg.facts_of(write)
+                    .into_iter()
+                    .any(|(fact, holds)| keys_differ(&flow, fact, holds, &first.account, &second.account))
+            });
+            if checked {
+                continue;
+            }
+
+            issues.push(
+                func.finding(
+                    ID,
+                    SEVERITY,
+                    second.span,
+                    format!(
+                        "{} '{}' writes to '{}' accounts '{}' and '{}' without checking that their keys differ, so the same account can be passed for both.",
+                        func.kind_label(),
+                        func.name(),
+                        data_type,
+                        first.binding,
+                        second.binding
+                    ),
+                )
+                .with_confidence(Confidence::Medium)
+                .with_remediation(&format!(
+                    "Return an error when `{}.key == {}.key` before modifying either account.",
+                    first.binding, second.binding
+                )),
+            );
+        }
+    }
+
+    issues
+}
+
+fn written_accounts<'a>(func: &FnItem<'a>, flow: &Dataflow) -> Vec<WrittenAccount<'a>> {
+    // (binding, account, write) in source order
+    let mut writes: Vec<(String, String, &'a Expr)> = Vec::new();
+    for_each_expr(func.block, |expr| {
+        if let Some(binding) = mutable_borrow_owner(expr) {
+            let account = flow.account_key(&binding, expr);
+            writes.push((binding, account, expr));
+        }
+    });
+
+    let mut written: Vec<WrittenAccount<'a>> = Vec::new();
+    for (binding, span) in account_bindings(func) {
+        let Some((_, account, _)) = writes.iter().find(|(written, _, _)| *written == binding) else {
+            continue;
+        };
+        // two names for one account are one written account
+        if written.iter().any(|other| other.account == *account) {
+            continue;
+        }
+        let Some(&(_, _, first_write)) = writes.iter().find(|(_, written, _)| written == account) else {
+            continue;
+        };
+        written.push(WrittenAccount {
+            data_type: data_type(func, &binding),
+            account: account.clone(),
+            first_write,
+            binding,
+            span,
+        });
+    }
+    written
+}
+
+// `vault` for `vault.data.borrow_mut()` or `vault.try_borrow_mut_data()`. moving lamports
+// between two aliases of one account nets out, so only data writes count
+fn mutable_borrow_owner(expr: &Expr) -> Option<String> {
+    let Expr::MethodCall(method_call) = expr else {
+        return None;
+    };
+    match method_call.method.to_string().as_str() {
+        "borrow_mut" => match strip(&method_call.receiver) {
+            Expr::Field(expr_field) if member_name(&expr_field.member) == "data" => root_ident(&expr_field.base),
+            _ => None,
+        },
+        "try_borrow_mut_data" => root_ident(&method_call.receiver),
+        _ => None,
+    }
+}
+
+// The struct a binding's data is deserialized into, e.g. `UserAccount` for
+// `let from_state = UserAccount::try_from_slice(&from.data.borrow())?`
+fn data_type(func: &FnItem, binding: &str) -> Option<String> {
+    let mut data_type = None;
+    visit_locals(func.block, |local| {
+        let Some(init) = &local.init else {
+            return;
+        };
+        let call = strip(&init.expr);
+        if is_deserialization_call(call)
+            && contains_expr(call, |inner| account_data_borrow_owner(inner).as_deref() == Some(binding))
+            && data_type.is_none()
+        {
+            data_type = deserialized_type(func, call);
+        }
+    });
+    data_type
+}
+
+// Whether `fact` evaluating to `holds` tells the keys of accounts `a` and `b` apart:
+// `from.key != to.key` holding or `from.key() == to.key()` failing, either way round
+fn keys_differ(flow: &Dataflow, fact: &Expr, holds: bool, a: &str, b: &str) -> bool {
+    let Some((left, right, false)) = equality(fact, holds) else {
+        return false;
+    };
+    let account = |side: &Expr| key_owner(side).map(|owner| flow.account_key(&owner, side));
+    match (account(left), account(right)) {
+        (Some(left), Some(right)) => (left == a && right == b) || (left == b && right == a),
+        _ => false,
+    }
+}
@@ -0,0 +1,117 @@
+use syn::spanned::Spanned;
+use syn::{BinOp, Expr};
+
+use super::account_close::{is_zero, lamports_borrow_owner, lamports_owner};
+use super::{AnalysisContext, Detector};
+use crate::anchor::context_struct;
+use crate::evidence::{account_evidence, Property};
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{call_name, contains_expr, for_each_expr, strip};
+use crate::walk::FnItem;
+
+const ID: &str = "unverified-lamport-debit";
+const SEVERITY: Severity = Severity::High;
+
+pub struct LamportDebitDetector;
+
+impl Detector for LamportDebitDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Lamports debited directly from an account whose owner is never checked against the program id."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions
+            .iter()
+            .flat_map(|func| check_lamport_debits(ctx, func))
+            .collect()
+    }
+}
+
+fn check_lamport_debits(ctx: &AnalysisContext, func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let evidence = account_evidence(func, &ctx.call_graph);
+    let accounts = context_struct(&ctx.accounts_structs, func);
+
+    for_each_expr(func.block, |expr| {
+        let Some(binding) = debited_account(expr) else {
+            return;
+        };
+        // typed Anchor accounts have their owner checked when they are loaded
+        if accounts
+            .and_then(|accounts| accounts.field(&binding))
+            .is_some_and(|field| field.is_owner_checked())
+        {
+            return;
+        }
+        let verified = |property| evidence.verified(&binding, property, expr);
+        if verified(Property::Owner) {
+            return;
+        }
+
+        let other_checks: Vec<&str> = [Property::Signer, Property::Writable]
+            .into_iter()
+            .filter(|property| verified(*property))
+            .map(Property::label)
+            .collect();
+        let (context, confidence) = if other_checks.is_empty() {
+            (String::new(), Confidence::Medium)
+        } else {
+            // a signer or writable check is easily mistaken for an ownership check
+            (
+                format!(" It only checks that '{}' {}.", binding, other_checks.join(" and ")),
+                Confidence::High,
+            )
+        };
+        issues.push(
+            func.finding(
+                ID,
+                SEVERITY,
+                expr.span(),
+                format!(
+                    "{} '{}' debits lamports from account '{}' without verifying that it is owned by the program.{}",
+                    func.kind_label(),
+                    func.name(),
+                    binding,
+                    context
+                ),
+            )
+            .with_confidence(confidence)
+            .with_remediation(&format!(
+                "Return `ProgramError::IncorrectProgramId` when `{}.owner != program_id` before moving its lamports.",
+                binding
+            )),
+        );
+    });
+
+    issues
+}
+
+// `vault` for `**vault.lamports.borrow_mut() -= amount` or
+// `**vault.try_borrow_mut_lamports()? = vault.lamports().checked_sub(amount)..`
+fn debited_account(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Binary(expr_binary) if matches!(expr_binary.op, BinOp::SubAssign(_)) => {
+            lamports_borrow_owner(strip(&expr_binary.left))
+        }
+        Expr::Assign(expr_assign) => {
+            let binding = lamports_borrow_owner(strip(&expr_assign.left))?;
+            let reads_own_balance = contains_expr(&expr_assign.right, |inner| {
+                lamports_owner(inner).as_deref() == Some(binding.as_str())
+            });
+            let subtracts = contains_expr(&expr_assign.right, |inner| match inner {
+                Expr::Binary(expr_binary) => matches!(expr_binary.op, BinOp::Sub(_)),
+                _ => call_name(inner).is_some_and(|name| name.ends_with("_sub")),
+            });
+            (is_zero(&expr_assign.right) || (reads_own_balance && subtracts)).then_some(binding)
+        }
+        _ => None,
+    }
+}
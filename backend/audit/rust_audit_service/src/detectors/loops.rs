@@ -0,0 +1,309 @@
+use std::collections::HashSet;
+
+use syn::spanned::Spanned;
+use syn::{BinOp, Expr, FnArg, Pat, Type};
+
+use super::arithmetic::{is_anchor_account_field, state_variables};
+use super::panic::program_functions;
+use super::pda::is_instruction_param;
+use super::{AnalysisContext, Detector};
+use crate::cfg::{equality, ordering, Cfg};
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{
+    call_name, contains_expr, for_each_expr, local_init, member_name, path_ident, render, root_ident, strip, strip_pat,
+};
+use crate::walk::FnItem;
+
+const ID: &str = "unbounded-loop";
+const SEVERITY: Severity = Severity::Medium;
+
+// how many `let` bindings we follow back when looking for where a loop bound came from
+const MAX_LOCAL_DEPTH: usize = 4;
+
+// iterator adapters that keep the length of the collection they are called on
+const ITERATOR_ADAPTERS: [&str; 8] = [
+    "iter",
+    "iter_mut",
+    "into_iter",
+    "enumerate",
+    "rev",
+    "skip",
+    "zip",
+    "chunks",
+];
+
+pub struct UnboundedLoopDetector;
+
+impl Detector for UnboundedLoopDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Loop bounded by instruction data, `remaining_accounts` or a stored vector without an upper limit, or recursion between program functions, which can exhaust the compute budget."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let program = program_functions(ctx);
+        let mut issues: Vec<Finding> = ctx
+            .functions
+            .iter()
+            .filter(|func| program.contains(&func.name()))
+            .flat_map(check_loop_bounds)
+            .collect();
+        issues.extend(check_recursion(ctx, &program));
+        issues
+    }
+}
+
+// Where the iteration count of a loop comes from
+enum BoundSource {
+    InstructionData(String),
+    RemainingAccounts,
+    StoredVector(String),
+}
+
+fn check_loop_bounds(func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let cfg = Cfg::new(func.block);
+    let state = state_variables(func);
+
+    for_each_expr(func.block, |expr| {
+        // the loop header, which the graph evaluates on its own rather than the whole loop
+        let (keyword, header, bounds) = match expr {
+            Expr::ForLoop(for_loop) => ("for", &*for_loop.expr, for_bounds(&for_loop.expr)),
+            Expr::While(while_loop) => ("while", &*while_loop.cond, while_bounds(&while_loop.cond)),
+            _ => return,
+        };
+        for bound in bounds {
+            let Some(source) = bound_source(func, &state, bound, expr, 0) else {
+                continue;
+            };
+            let limited = cfg
+                .facts_of(header)
+                .into_iter()
+                .any(|(fact, holds)| limits(fact, holds, bound));
+            if limited {
+                continue;
+            }
+
+            let (origin, confidence) = match &source {
+                BoundSource::InstructionData(param) => (format!("instruction data ('{}')", param), Confidence::High),
+                BoundSource::RemainingAccounts => ("`remaining_accounts`".to_string(), Confidence::Medium),
+                BoundSource::StoredVector(field) => (format!("account data ('{}')", field), Confidence::Medium),
+            };
+            issues.push(
+                func.finding(
+                    ID,
+                    SEVERITY,
+                    expr.span(),
+                    format!(
+                        "{} '{}' runs a `{}` loop bounded by `{}`, which comes from {} and is never capped, so a caller can make it exhaust the compute budget.",
+                        func.kind_label(),
+                        func.name(),
+                        keyword,
+                        render(bound),
+                        origin
+                    ),
+                )
+                .with_confidence(confidence)
+                .with_remediation(&format!(
+                    "Return an error when `{}` exceeds a fixed maximum before the loop, or iterate over `.take(MAX)`.",
+                    render(bound)
+                )),
+            );
+            break;
+        }
+    });
+
+    issues
+}
+
+// `n` for `for i in 0..n`, `items` for `for item in items.iter().enumerate()`. nothing
+// for iterators already capped with `.take(..)`
+fn for_bounds(iter: &Expr) -> Vec<&Expr> {
+    match strip(iter) {
+        Expr::Range(range) => range.end.iter().map(|end| &**end).collect(),
+        Expr::MethodCall(method_call) if method_call.method == "take" => Vec::new(),
+        Expr::MethodCall(method_call) if ITERATOR_ADAPTERS.contains(&method_call.method.to_string().as_str()) => {
+            for_bounds(&method_call.receiver)
+        }
+        expr => vec![expr],
+    }
+}
+
+// Both sides of the comparisons in `while i < n`, or the iterator of `while let Some(x) = it.next()`
+fn while_bounds(cond: &Expr) -> Vec<&Expr> {
+    match strip(cond) {
+        Expr::Binary(expr_binary) => match expr_binary.op {
+            BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_) | BinOp::Ge(_) => {
+                if is_fixed_limit(&expr_binary.left) || is_fixed_limit(&expr_binary.right) {
+                    return Vec::new();
+                }
+                vec![&*expr_binary.left, &*expr_binary.right]
+            }
+            // `i < n && i < MAX` is capped by its second half
+            BinOp::And(_) => {
+                let (left, right) = (while_bounds(&expr_binary.left), while_bounds(&expr_binary.right));
+                if left.is_empty() || right.is_empty() {
+                    Vec::new()
+                } else {
+                    left.into_iter().chain(right).collect()
+                }
+            }
+            _ => Vec::new(),
+        },
+        Expr::Let(expr_let) => match strip(&expr_let.expr) {
+            Expr::MethodCall(method_call) if method_call.method == "next" => for_bounds(&method_call.receiver),
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+fn bound_source(func: &FnItem, state: &HashSet<String>, bound: &Expr, at: &Expr, depth: usize) -> Option<BoundSource> {
+    if contains_expr(
+        bound,
+        |expr| matches!(expr, Expr::Field(field) if member_name(&field.member) == "remaining_accounts"),
+    ) {
+        return Some(BoundSource::RemainingAccounts);
+    }
+    if contains_expr(bound, |expr| {
+        call_name(expr).is_some_and(|name| name == "min" || name == "take")
+    }) {
+        return None;
+    }
+
+    let mut stored = None;
+    contains_expr(bound, |expr| {
+        if let Expr::Field(expr_field) = expr {
+            let on_state = root_ident(&expr_field.base).is_some_and(|root| state.contains(&root));
+            if on_state || is_anchor_account_field(&expr_field.base) {
+                stored = Some(render(expr));
+            }
+        }
+        stored.is_some()
+    });
+    if let Some(field) = stored {
+        return Some(BoundSource::StoredVector(field));
+    }
+
+    let mut idents = Vec::new();
+    contains_expr(bound, |expr| {
+        if let Some(ident) = path_ident(expr) {
+            idents.push(ident);
+        }
+        false
+    });
+    for ident in idents {
+        if is_instruction_param(func, &ident) {
+            // the raw instruction bytes are capped by the transaction size
+            if path_ident(bound).as_deref() == Some(ident.as_str()) && is_byte_slice_param(func, &ident) {
+                continue;
+            }
+            return Some(BoundSource::InstructionData(ident));
+        }
+        if depth >= MAX_LOCAL_DEPTH {
+            continue;
+        }
+        if let Some(source) =
+            local_init(func.block, &ident, at).and_then(|init| bound_source(func, state, init, at, depth + 1))
+        {
+            return Some(source);
+        }
+    }
+    None
+}
+
+fn is_byte_slice_param(func: &FnItem, name: &str) -> bool {
+    func.sig.inputs.iter().any(|input| match input {
+        FnArg::Typed(pat_type) => {
+            matches!(strip_pat(&pat_type.pat), Pat::Ident(pat_ident) if pat_ident.ident == name)
+                && matches!(&*pat_type.ty, Type::Reference(reference) if matches!(&*reference.elem, Type::Slice(_)))
+        }
+        FnArg::Receiver(_) => false,
+    })
+}
+
+// `MAX_ITEMS` or `16`
+fn is_fixed_limit(expr: &Expr) -> bool {
+    match strip(expr) {
+        Expr::Lit(_) => true,
+        expr => path_ident(expr).is_some_and(|name| {
+            name.len() > 1
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        }),
+    }
+}
+
+// Whether `fact` evaluating to `holds` caps `bound`, e.g. `count > MAX_ITEMS` failing after
+// `if count > MAX_ITEMS { return Err(..) }`, or `ctx.remaining_accounts.len() <= 8` holding
+// inside `if ctx.remaining_accounts.len() <= 8 { .. }` around the loop
+fn limits(fact: &Expr, holds: bool, bound: &Expr) -> bool {
+    let bound = render(bound);
+    if let Some((_, smaller, _)) = ordering(fact, holds) {
+        return render(smaller).contains(&bound);
+    }
+    match equality(fact, holds) {
+        Some((left, right, true)) => render(left).contains(&bound) || render(right).contains(&bound),
+        _ => false,
+    }
+}
+
+// Program functions that can call themselves again, directly or through other functions.
+// calls are matched by name, like the rest of the reachability analysis
+fn check_recursion(ctx: &AnalysisContext, program: &HashSet<String>) -> Vec<Finding> {
+    let functions: Vec<&FnItem> = ctx
+        .functions
+        .iter()
+        .filter(|func| program.contains(&func.name()))
+        .collect();
+    let names: HashSet<String> = functions.iter().map(|func| func.name()).collect();
+
+    let mut issues = Vec::new();
+    for func in functions {
+        let name = func.name();
+        let mut call = None;
+        for_each_expr(func.block, |expr| {
+            if call.is_some() {
+                return;
+            }
+            if let Some(callee) = call_name(expr).filter(|callee| names.contains(callee)) {
+                if ctx.call_graph.reaches(&callee, &name) {
+                    call = Some((expr, callee));
+                }
+            }
+        });
+        let Some((call, callee)) = call else {
+            continue;
+        };
+
+        let path = if callee == name {
+            "calls itself".to_string()
+        } else {
+            format!("calls itself again through '{}'", callee)
+        };
+        issues.push(
+            func.finding(
+                ID,
+                SEVERITY,
+                call.span(),
+                format!(
+                    "{} '{}' {}, so its depth depends on the input and can exhaust the compute budget or the stack.",
+                    func.kind_label(),
+                    name,
+                    path
+                ),
+            )
+            .with_confidence(Confidence::Medium)
+            .with_remediation("Rewrite the recursion as a loop with a fixed maximum number of iterations."),
+        );
+    }
+    issues
+}
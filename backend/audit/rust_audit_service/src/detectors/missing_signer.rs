@@ -0,0 +1,143 @@
+use syn::spanned::Spanned;
+use syn::{BinOp, Expr};
+
+use super::{AnalysisContext, Detector};
+use crate::evidence::{account_evidence, Property};
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{account_bindings, call_args, call_name, for_each_expr, key_owner, member_name, root_ident, strip};
+use crate::walk::FnItem;
+
+const ID: &str = "missing-signer-check";
+const SEVERITY: Severity = Severity::High;
+
+// Stored fields an account key is compared against when it acts as an authority
+const AUTHORITY_FIELDS: [&str; 7] = [
+    "authority",
+    "admin",
+    "owner",
+    "payer",
+    "signer",
+    "manager",
+    "update_authority",
+];
+
+pub struct MissingSignerDetector;
+
+impl Detector for MissingSignerDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "AccountInfo used as authority, admin or payer without an `is_signer` check on the same account that returns an error."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions
+            .iter()
+            .flat_map(|func| check_missing_signer(ctx, func))
+            .collect()
+    }
+}
+
+fn check_missing_signer(ctx: &AnalysisContext, func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let evidence = account_evidence(func, &ctx.call_graph);
+
+    for (binding, _) in account_bindings(func) {
+        // a check before the first use says nothing about a use on another path
+        let unverified = authority_uses(func, &binding)
+            .into_iter()
+            .find(|(use_expr, _)| !evidence.verified(&binding, Property::Signer, *use_expr));
+        let Some((use_expr, role)) = unverified else {
+            continue;
+        };
+
+        issues.push(
+            func.finding(
+                ID,
+                SEVERITY,
+                use_expr.span(),
+                format!(
+                    "{} '{}' uses account '{}' as {} without checking that it signed the transaction.",
+                    func.kind_label(),
+                    func.name(),
+                    binding,
+                    role
+                ),
+            )
+            .with_confidence(Confidence::Medium)
+            .with_remediation(&format!(
+                "Return `ProgramError::MissingRequiredSignature` when `!{}.is_signer` before using it.",
+                binding
+            )),
+        );
+    }
+
+    issues
+}
+
+// Every expression that treats `binding` as an authority, with the role it plays
+fn authority_uses<'a>(func: &FnItem<'a>, binding: &str) -> Vec<(&'a Expr, &'static str)> {
+    let mut uses = Vec::new();
+    for_each_expr(func.block, |expr| {
+        if let Some(role) = authority_role(expr, binding) {
+            uses.push((expr, role));
+        }
+    });
+    uses
+}
+
+fn authority_role(expr: &Expr, binding: &str) -> Option<&'static str> {
+    match expr {
+        // `state.authority != *authority.key`
+        Expr::Binary(expr_binary) if matches!(expr_binary.op, BinOp::Eq(_) | BinOp::Ne(_)) => {
+            let (left, right) = (&*expr_binary.left, &*expr_binary.right);
+            if (key_owner(left).as_deref() == Some(binding) && is_stored_authority(right, binding))
+                || (key_owner(right).as_deref() == Some(binding) && is_stored_authority(left, binding))
+            {
+                Some("an authority")
+            } else {
+                None
+            }
+        }
+        // `spl_token::instruction::transfer(.., authority.key, ..)` or
+        // `system_instruction::transfer(payer.key, ..)`
+        Expr::Call(_) => {
+            let args = call_args(expr);
+            let (index, role) = authority_argument(&call_name(expr)?, args.len())?;
+            args.get(index)
+                .filter(|arg| root_ident(arg).as_deref() == Some(binding))
+                .map(|_| role)
+        }
+        _ => None,
+    }
+}
+
+// Which argument of a well known instruction builder is the signing authority
+fn authority_argument(function: &str, arg_count: usize) -> Option<(usize, &'static str)> {
+    match (function, arg_count) {
+        ("transfer", 3) | ("create_account", 5) | ("transfer_with_seed", 6) => Some((0, "a payer")),
+        ("revoke", _) => Some((2, "an authority")),
+        ("transfer" | "burn" | "burn_checked" | "mint_to" | "mint_to_checked" | "approve" | "close_account", _) => {
+            Some((3, "an authority"))
+        }
+        ("transfer_checked" | "approve_checked" | "set_authority", _) => Some((4, "an authority")),
+        _ => None,
+    }
+}
+
+// A stored authority field read from some other value, e.g. `vault_state.authority`
+fn is_stored_authority(expr: &Expr, binding: &str) -> bool {
+    match strip(expr) {
+        Expr::Field(expr_field) => {
+            AUTHORITY_FIELDS.contains(&member_name(&expr_field.member).as_str())
+                && root_ident(&expr_field.base).as_deref() != Some(binding)
+        }
+        _ => false,
+    }
+}
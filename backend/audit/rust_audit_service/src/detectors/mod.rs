@@ -0,0 +1,217 @@
+mod access_control;
+mod account_close;
+mod anchor_constraints;
+mod arbitrary_cpi;
+mod arithmetic;
+mod duplicate_accounts;
+mod lamports;
+mod loops;
+mod missing_signer;
+mod ownership;
+mod panic;
+mod pda;
+mod precision;
+mod reinitialization;
+mod rent;
+mod slippage;
+mod summary;
+mod sysvar;
+mod taint;
+mod token_account;
+mod type_cosplay;
+
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::anchor::{self, AccountsStruct};
+use crate::finding::{Finding, Severity};
+use crate::program::Program;
+use crate::taint::Taint;
+use crate::walk::{self, FnItem};
+
+pub use access_control::AccessControlDetector;
+pub use account_close::AccountCloseDetector;
+pub use anchor_constraints::AnchorConstraintsDetector;
+pub use arbitrary_cpi::ArbitraryCpiDetector;
+pub use arithmetic::UncheckedArithmeticDetector;
+pub use duplicate_accounts::DuplicateMutableAccountsDetector;
+pub use lamports::LamportDebitDetector;
+pub use loops::UnboundedLoopDetector;
+pub use missing_signer::MissingSignerDetector;
+pub use ownership::OwnershipDetector;
+pub use panic::PanicPathDetector;
+pub use pda::PdaValidationDetector;
+pub use precision::PrecisionLossDetector;
+pub use reinitialization::ReinitializationDetector;
+pub use rent::RentExemptionDetector;
+pub use slippage::SlippageDetector;
+pub use summary::{CallGraph, Sink, Summary};
+pub use sysvar::SysvarSpoofingDetector;
+pub use taint::{TaintDetector, TaintSink};
+pub use token_account::TokenAccountValidationDetector;
+pub use type_cosplay::TypeCosplayDetector;
+
+// Everything a detector gets to look at for one audit
+pub struct AnalysisContext<'a> {
+    pub program: &'a Program,
+    // every function body in the program, see `walk::functions`
+    pub functions: Vec<FnItem<'a>>,
+    // `#[derive(Accounts)]` structs, empty for native programs
+    pub accounts_structs: Vec<AccountsStruct<'a>>,
+    // calls between the functions above and what each of them checks and reaches
+    pub call_graph: CallGraph,
+    // attacker controlled bindings of each function above, in the same order, `None` for
+    // the functions instruction data does not reach
+    pub taint: Vec<Option<Taint>>,
+}
+
+impl<'a> AnalysisContext<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        let functions = walk::functions(program);
+        let call_graph = CallGraph::new(&functions);
+        let mut ctx = AnalysisContext {
+            program,
+            functions,
+            accounts_structs: anchor::accounts_structs(program),
+            call_graph,
+            taint: Vec::new(),
+        };
+        // the same for every `taint-*` rule, so it is worked out once
+        ctx.taint = taint::tainted_functions(&ctx);
+        ctx
+    }
+}
+
+pub trait Detector: Send + Sync {
+    // stable rule id, also used as `rule_id` on every finding the detector emits
+    fn id(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    fn default_severity(&self) -> Severity;
+
+    // noisy or experimental rules can opt out and be enabled per request
+    fn enabled_by_default(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding>;
+}
+
+// What `GET /detectors` returns for each registered rule
+#[derive(Serialize, Clone, Debug)]
+pub struct DetectorInfo {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub default_severity: Severity,
+    pub enabled: bool,
+}
+
+pub struct DetectorRegistry {
+    detectors: Vec<Box<dyn Detector>>,
+    enabled: HashSet<&'static str>,
+}
+
+impl DetectorRegistry {
+    pub fn empty() -> Self {
+        DetectorRegistry {
+            detectors: Vec::new(),
+            enabled: HashSet::new(),
+        }
+    }
+
+    pub fn register(&mut self, detector: Box<dyn Detector>) {
+        let id = detector.id();
+        if self.contains(id) {
+            panic!("detector '{}' is registered twice", id);
+        }
+        if detector.enabled_by_default() {
+            self.enabled.insert(id);
+        }
+        self.detectors.push(detector);
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.detectors.iter().any(|detector| detector.id() == id)
+    }
+
+    // returns false when no detector has that id
+    pub fn enable(&mut self, id: &str) -> bool {
+        match self.detectors.iter().find(|detector| detector.id() == id) {
+            Some(detector) => {
+                self.enabled.insert(detector.id());
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn disable(&mut self, id: &str) -> bool {
+        if !self.contains(id) {
+            return false;
+        }
+        self.enabled.remove(id);
+        true
+    }
+
+    pub fn is_enabled(&self, id: &str) -> bool {
+        self.enabled.contains(id)
+    }
+
+    pub fn list(&self) -> Vec<DetectorInfo> {
+        self.detectors
+            .iter()
+            .map(|detector| DetectorInfo {
+                id: detector.id(),
+                description: detector.description(),
+                default_severity: detector.default_severity(),
+                enabled: self.is_enabled(detector.id()),
+            })
+            .collect()
+    }
+
+    pub fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        self.run_filtered(ctx, |id| self.is_enabled(id))
+    }
+
+    // runs every detector whose id passes `selected`, regardless of the registry's own
+    // enabled set, so callers can apply per-request overrides
+    pub fn run_filtered<F: Fn(&str) -> bool>(&self, ctx: &AnalysisContext, selected: F) -> Vec<Finding> {
+        self.detectors
+            .iter()
+            .filter(|detector| selected(detector.id()))
+            .flat_map(|detector| detector.run(ctx))
+            .collect()
+    }
+}
+
+impl Default for DetectorRegistry {
+    fn default() -> Self {
+        let mut registry = DetectorRegistry::empty();
+        registry.register(Box::new(AccessControlDetector));
+        registry.register(Box::new(OwnershipDetector));
+        registry.register(Box::new(SlippageDetector));
+        registry.register(Box::new(RentExemptionDetector));
+        registry.register(Box::new(MissingSignerDetector));
+        registry.register(Box::new(ArbitraryCpiDetector));
+        registry.register(Box::new(PdaValidationDetector));
+        registry.register(Box::new(UncheckedArithmeticDetector));
+        registry.register(Box::new(AccountCloseDetector));
+        registry.register(Box::new(TypeCosplayDetector));
+        registry.register(Box::new(DuplicateMutableAccountsDetector));
+        registry.register(Box::new(ReinitializationDetector));
+        registry.register(Box::new(SysvarSpoofingDetector));
+        registry.register(Box::new(PrecisionLossDetector));
+        registry.register(Box::new(PanicPathDetector));
+        registry.register(Box::new(TokenAccountValidationDetector));
+        registry.register(Box::new(AnchorConstraintsDetector));
+        registry.register(Box::new(LamportDebitDetector));
+        registry.register(Box::new(UnboundedLoopDetector));
+        registry.register(Box::new(TaintDetector::new(TaintSink::Lamports)));
+        registry.register(Box::new(TaintDetector::new(TaintSink::ProgramId)));
+        registry.register(Box::new(TaintDetector::new(TaintSink::PdaSeeds)));
+        registry.register(Box::new(TaintDetector::new(TaintSink::Index)));
+        registry.register(Box::new(TaintDetector::new(TaintSink::LoopBound)));
+        registry
+    }
+}
@@ -0,0 +1,187 @@
+use syn::spanned::Spanned;
+use syn::{BinOp, Expr, ExprBinary, ExprCall};
+
+use super::pda::is_instruction_param;
+use super::token_account::unpacked_from;
+use super::{AnalysisContext, Detector};
+use crate::cfg::Cfg;
+use crate::dataflow::Dataflow;
+use crate::evidence::Property;
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{call_args, for_each_expr, member_name, path_ident, root_ident, strip};
+use crate::walk::FnItem;
+
+const ID: &str = "missing-ownership-check";
+const SEVERITY: Severity = Severity::High;
+
+pub struct OwnershipDetector;
+
+impl Detector for OwnershipDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Account data deserialized before the account owner is compared against the program id."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions
+            .iter()
+            .flat_map(|func| check_account_ownership(ctx, func))
+            .collect()
+    }
+}
+
+fn check_account_ownership(ctx: &AnalysisContext, func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let cfg = Cfg::new(func.block);
+    let flow = Dataflow::new(func);
+    let mut deserializations = Vec::new();
+    // `assert_owned_by(vault, program_id)?` and other helpers whose summary checks an owner
+    let mut owner_helpers = Vec::new();
+
+    for_each_expr(func.block, |expr| {
+        for arg in ctx.call_graph.verified_args(expr, Property::Owner) {
+            owner_helpers.push((expr, arg));
+        }
+        // `AccountData::try_from_slice(instruction_data)` reads no account
+        let parses_instruction_data = call_args(expr)
+            .iter()
+            .any(|arg| path_ident(arg).is_some_and(|name| is_instruction_param(func, &name)));
+        if is_deserialization_call(expr) && !matches!(expr, Expr::Try(_)) && !parses_instruction_data {
+            deserializations.push(expr);
+        }
+    });
+
+    for call in deserializations {
+        // the account whose data is read, when the borrow can be traced back to one
+        let account = call_args(call)
+            .into_iter()
+            .find_map(|arg| unpacked_from(func, arg, call))
+            .map(|binding| flow.account_key(&binding, call));
+        // only an owner comparison on that account whose failing branch leaves the function,
+        // on every path to the call
+        let has_ownership_check = cfg
+            .guards_of(call)
+            .into_iter()
+            .any(|cond| is_ownership_check_condition(cond, &flow, account.as_deref()))
+            || owner_helpers.iter().any(|(helper, arg)| {
+                let checked = root_ident(arg).map(|binding| flow.account_key(&binding, *arg));
+                (account.is_none() || checked == account) && cfg.runs_before(*helper, call)
+            });
+        if !has_ownership_check {
+            issues.push(
+                func.finding(
+                    ID,
+                    SEVERITY,
+                    call.span(),
+                    format!(
+                        "{} '{}' deserializes an account without checking ownership.",
+                        func.kind_label(),
+                        func.name()
+                    ),
+                )
+                .with_confidence(Confidence::Medium)
+                .with_remediation("Check that `account.owner == program_id` before deserializing the account data."),
+            );
+        }
+    }
+
+    issues
+}
+
+pub(crate) fn is_deserialization_call(expr: &Expr) -> bool {
+    match expr {
+        Expr::MethodCall(method_call) => {
+            let method_name = method_call.method.to_string();
+            let deserialization_methods = ["try_from_slice", "unpack", "deserialize"];
+            deserialization_methods.contains(&method_name.as_str())
+        }
+        Expr::Call(ExprCall { func, .. }) => {
+            if let Expr::Path(expr_path) = func.as_ref() {
+                let segments = &expr_path.path.segments;
+                if let Some(last_segment) = segments.last() {
+                    let func_name = last_segment.ident.to_string();
+                    let deserialization_methods = ["try_from_slice", "unpack", "deserialize"];
+                    deserialization_methods.contains(&func_name.as_str())
+                } else {
+                    false
+                }
+            } else {
+                false
+            }
+        }
+        Expr::Try(expr_try) => {
+            is_deserialization_call(&expr_try.expr)
+        }
+        _ => false,
+    }
+}
+
+fn is_ownership_check_condition(expr: &Expr, flow: &Dataflow, account: Option<&str>) -> bool {
+    match expr {
+        // `owner != program_id || !is_writable`
+        Expr::Binary(ExprBinary { left, op: BinOp::Or(_) | BinOp::And(_), right, .. }) => {
+            is_ownership_check_condition(left, flow, account) || is_ownership_check_condition(right, flow, account)
+        }
+        // binary ex `==` or `!=` also check for possible orders lol
+        Expr::Binary(ExprBinary { left, op, right, .. }) => {
+            if matches!(op, BinOp::Eq(_) | BinOp::Ne(_)) {
+                (is_account_owner_expr(left, flow, account) && is_program_id_expr(right))
+                    || (is_account_owner_expr(right, flow, account) && is_program_id_expr(left))
+            } else {
+                false
+            }
+        }
+        Expr::Unary(expr_unary) => is_ownership_check_condition(&expr_unary.expr, flow, account),
+        Expr::Paren(expr_paren) => is_ownership_check_condition(&expr_paren.expr, flow, account),
+        _ => false,
+    }
+}
+
+// `vault.owner` on a binding that holds an account, or `vault.owner()`. when we know which
+// account is deserialized the check has to be on that one
+fn is_account_owner_expr(expr: &Expr, flow: &Dataflow, account: Option<&str>) -> bool {
+    let is_checked_account = |checked: Option<String>| match (checked, account) {
+        (Some(checked), Some(account)) => checked == account,
+        (checked, None) => checked.is_some(),
+        (None, Some(_)) => false,
+    };
+    match strip(expr) {
+        Expr::Field(expr_field) => {
+            let field_ident = member_name(&expr_field.member).to_lowercase();
+            (field_ident == "owner" || field_ident == "key")
+                && is_checked_account(path_ident(&expr_field.base).and_then(|base| flow.account(&base, expr)))
+        }
+        Expr::MethodCall(expr_method) => {
+            let method_name = expr_method.method.to_string().to_lowercase();
+            (method_name == "owner" || method_name == "key")
+                && is_checked_account(
+                    root_ident(&expr_method.receiver)
+                        .map(|receiver| flow.account_key(&receiver, expr))
+                        .or_else(|| account.map(str::to_string)),
+                )
+        }
+        _ => false,
+    }
+}
+
+// `program_id`, or `*program_id` as `require_keys_eq!` compares pubkeys by value
+fn is_program_id_expr(expr: &Expr) -> bool {
+    match strip(expr) {
+        Expr::Path(expr_path) => {
+            let ident = expr_path.path.segments.last().unwrap().ident.to_string().to_lowercase();
+            ident.contains("program_id") || ident.ends_with("_id") || ident == "id"
+        }
+        Expr::Field(expr_field) => {
+            let field_ident = member_name(&expr_field.member).to_lowercase();
+            field_ident.contains("program_id") || field_ident.ends_with("_id") || field_ident == "id"
+        }
+        _ => false,
+    }
+}
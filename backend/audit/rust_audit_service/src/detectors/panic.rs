@@ -0,0 +1,299 @@
+use std::collections::HashSet;
+
+use proc_macro2::TokenTree;
+use syn::spanned::Spanned;
+use syn::{BinOp, Expr, FnArg, Item, Lit, RangeLimits, Type};
+
+use super::account_close::is_zero;
+use super::{AnalysisContext, Detector};
+use crate::cfg::{equality, ordering, Cfg};
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{for_each_expr, for_each_macro, is_account_info_type, render, same_value, strip};
+use crate::walk::{self, type_name, FnItem, FnKind};
+
+const ID: &str = "panic-path";
+const SEVERITY: Severity = Severity::Low;
+
+const PANIC_MACROS: [&str; 4] = ["panic", "unreachable", "todo", "unimplemented"];
+
+pub struct PanicPathDetector;
+
+impl Detector for PanicPathDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "`unwrap`, `expect`, unchecked indexing, `panic!`-style macros or division by a possibly-zero value in code reachable from the entrypoint."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let reachable = reachable_functions(ctx);
+        ctx.functions
+            .iter()
+            .filter(|func| match &reachable {
+                Some(names) => names.contains(&func.name()),
+                None => !is_test_function(func),
+            })
+            .flat_map(check_panic_paths)
+            .collect()
+    }
+}
+
+fn check_panic_paths(func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let cfg = Cfg::new(func.block);
+    let mut report = |span, what: String, remediation: &str, confidence| {
+        issues.push(
+            func.finding(
+                ID,
+                SEVERITY,
+                span,
+                format!(
+                    "{} '{}' can panic at {}, aborting the transaction with an opaque error.",
+                    func.kind_label(),
+                    func.name(),
+                    what
+                ),
+            )
+            .with_confidence(confidence)
+            .with_remediation(remediation),
+        );
+    };
+
+    for_each_expr(func.block, |expr| match expr {
+        Expr::MethodCall(method_call) if method_call.method == "unwrap" || method_call.method == "expect" => {
+            report(
+                method_call.method.span(),
+                format!("`.{}()` on `{}`", method_call.method, render(&method_call.receiver)),
+                "Return a `ProgramError` instead, e.g. `.ok_or(ProgramError::InvalidArgument)?` or `.map_err(..)?`.",
+                Confidence::High,
+            );
+        }
+        Expr::Index(expr_index)
+            if !is_full_range(&expr_index.index) && !is_length_checked(&cfg, expr, &expr_index.expr, &expr_index.index) =>
+        {
+            report(
+                expr.span(),
+                format!("the unchecked index `{}`", render(expr)),
+                "Use `.get(..)` and return `ProgramError::NotEnoughAccountKeys` or `InvalidAccountData` when it is `None`.",
+                Confidence::Medium,
+            );
+        }
+        Expr::Binary(expr_binary)
+            if matches!(
+                expr_binary.op,
+                BinOp::Div(_) | BinOp::Rem(_) | BinOp::DivAssign(_) | BinOp::RemAssign(_)
+            ) && !is_nonzero_literal(&expr_binary.right)
+                && !is_zero_checked(&cfg, expr, &expr_binary.right) =>
+        {
+            report(
+                expr.span(),
+                format!(
+                    "`{}`, which divides by zero when `{}` is 0",
+                    render(expr),
+                    render(&expr_binary.right)
+                ),
+                "Use `checked_div` and return a `ProgramError` when the divisor is zero.",
+                Confidence::Medium,
+            );
+        }
+        _ => {}
+    });
+
+    for_each_macro(func.block, |mac| {
+        let name = mac
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default();
+        if PANIC_MACROS.contains(&name.as_str()) {
+            report(
+                mac.span(),
+                format!("`{}!`", name),
+                "Return a `ProgramError` (or a custom error) instead of panicking.",
+                Confidence::High,
+            );
+        }
+    });
+
+    issues
+}
+
+// `[..]` never goes out of bounds
+fn is_full_range(index: &Expr) -> bool {
+    matches!(index, Expr::Range(range) if range.start.is_none() && range.end.is_none() && matches!(range.limits, RangeLimits::HalfOpen(_)))
+}
+
+fn is_nonzero_literal(expr: &Expr) -> bool {
+    match strip(expr) {
+        Expr::Lit(expr_lit) => match &expr_lit.lit {
+            Lit::Int(lit_int) => lit_int.base10_digits() != "0",
+            Lit::Float(_) => true,
+            _ => false,
+        },
+        // constants such as `PRECISION` or `u64::MAX`
+        Expr::Path(expr_path) => expr_path.path.segments.last().is_some_and(|segment| {
+            segment
+                .ident
+                .to_string()
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c == '_')
+        }),
+        _ => false,
+    }
+}
+
+// A bound on `indexed.len()` known where `at` runs that keeps `index` in range, e.g.
+// `if accounts.len() < 4 { return Err(..) }` before `accounts[3]`, or `i < data.len()`
+// around `data[i]`
+fn is_length_checked(cfg: &Cfg, at: &Expr, indexed: &Expr, index: &Expr) -> bool {
+    // the length has to exceed `bound`, or only reach it when `strict` is false
+    let (bound, strict) = match strip(index) {
+        Expr::Range(range) => match (&range.end, &range.limits) {
+            (Some(end), RangeLimits::HalfOpen(_)) => (&**end, false),
+            (Some(end), RangeLimits::Closed(_)) => (&**end, true),
+            (None, _) => match &range.start {
+                Some(start) => (&**start, false),
+                None => return true,
+            },
+        },
+        index => (index, true),
+    };
+    cfg.facts_of(at).into_iter().any(|(fact, holds)| {
+        let Some((greater, smaller, known_strict)) = ordering(fact, holds) else {
+            return false;
+        };
+        let is_len = matches!(strip(greater), Expr::MethodCall(method_call)
+            if method_call.method == "len" && method_call.args.is_empty() && same_value(&method_call.receiver, indexed));
+        if !is_len {
+            return false;
+        }
+        if same_value(smaller, bound) {
+            return known_strict || !strict;
+        }
+        match (int_value(smaller), int_value(bound)) {
+            (Some(known), Some(bound)) => known + u128::from(known_strict) >= bound + u128::from(strict),
+            _ => false,
+        }
+    })
+}
+
+// A condition known where `at` runs that rules out a zero divisor: `divisor != 0`
+// holding, `divisor == 0` failing or `divisor > 0`
+fn is_zero_checked(cfg: &Cfg, at: &Expr, divisor: &Expr) -> bool {
+    cfg.facts_of(at).into_iter().any(|(fact, holds)| {
+        if let Some((left, right, false)) = equality(fact, holds) {
+            return (same_value(left, divisor) && is_zero(right)) || (same_value(right, divisor) && is_zero(left));
+        }
+        match ordering(fact, holds) {
+            Some((greater, smaller, strict)) => {
+                same_value(greater, divisor) && int_value(smaller).is_some_and(|value| value + u128::from(strict) >= 1)
+            }
+            None => false,
+        }
+    })
+}
+
+fn int_value(expr: &Expr) -> Option<u128> {
+    match strip(expr) {
+        Expr::Lit(expr_lit) => match &expr_lit.lit {
+            Lit::Int(lit_int) => lit_int.base10_parse().ok(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_test_function(func: &FnItem) -> bool {
+    func.attrs.iter().any(|attr| attr.path().is_ident("test"))
+        || func.module_path.iter().any(|module| module == "tests")
+}
+
+// Names of the functions reachable from the entrypoint by following calls by name, or
+// `None` when the program has no recognizable entrypoint (a lone snippet, say)
+fn reachable_functions(ctx: &AnalysisContext) -> Option<HashSet<String>> {
+    let roots = entrypoints(ctx);
+    if roots.is_empty() {
+        return None;
+    }
+    Some(ctx.call_graph.reachable_from(roots))
+}
+
+// Where execution enters the program: the entrypoint or, in a snippet without a
+// recognizable one (a lone handler, say), every function that takes accounts
+pub(crate) fn program_roots(ctx: &AnalysisContext) -> HashSet<String> {
+    let roots = entrypoints(ctx);
+    if !roots.is_empty() {
+        return roots;
+    }
+    ctx.functions
+        .iter()
+        .filter(|func| takes_accounts(func) && !is_test_function(func))
+        .map(|func| func.name())
+        .collect()
+}
+
+// Functions that run on chain: the program roots and everything they call, by name
+pub(crate) fn program_functions(ctx: &AnalysisContext) -> HashSet<String> {
+    ctx.call_graph.reachable_from(program_roots(ctx))
+}
+
+// `accounts: &[AccountInfo]`, `vault: &AccountInfo` or Anchor's `ctx: Context<..>`
+fn takes_accounts(func: &FnItem) -> bool {
+    func.sig.inputs.iter().any(|input| match input {
+        FnArg::Typed(pat_type) => match &*pat_type.ty {
+            Type::Reference(reference) => match &*reference.elem {
+                Type::Slice(slice) => is_account_info_type(&slice.elem),
+                elem => is_account_info_type(elem),
+            },
+            ty => is_account_info_type(ty) || type_name(ty).as_deref() == Some("Context"),
+        },
+        FnArg::Receiver(_) => false,
+    })
+}
+
+// The function named in `entrypoint!(..)`, every handler of an Anchor `#[program]`
+// module, or `process_instruction` when neither is present
+fn entrypoints(ctx: &AnalysisContext) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let mut program_modules = Vec::new();
+
+    for module_item in walk::module_items(ctx.program) {
+        match module_item.item {
+            Item::Macro(item_macro)
+                if item_macro
+                    .mac
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "entrypoint") =>
+            {
+                if let Some(TokenTree::Ident(ident)) = item_macro.mac.tokens.clone().into_iter().next() {
+                    names.insert(ident.to_string());
+                }
+            }
+            Item::Mod(item_mod) if item_mod.attrs.iter().any(|attr| attr.path().is_ident("program")) => {
+                let mut path = module_item.module_path.clone();
+                path.push(item_mod.ident.to_string());
+                program_modules.push(path);
+            }
+            _ => {}
+        }
+    }
+
+    for func in &ctx.functions {
+        if func.kind == FnKind::Free && program_modules.contains(&func.module_path) {
+            names.insert(func.name());
+        }
+    }
+    if names.is_empty() && ctx.functions.iter().any(|func| func.name() == "process_instruction") {
+        names.insert("process_instruction".to_string());
+    }
+    names
+}
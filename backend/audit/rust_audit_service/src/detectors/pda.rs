@@ -0,0 +1,434 @@
+use std::ptr;
+
+use syn::spanned::Spanned;
+use syn::{Expr, FnArg, Pat, Type};
+
+use super::ownership::is_deserialization_call;
+use super::token_account::unpacked_from;
+use super::{AnalysisContext, Detector};
+use crate::cfg::{equality, for_each_fact, Cfg};
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{
+    call_args, call_name, contains_expr, for_each_expr, is_account_info_type, key_owner, local_init, member_name,
+    pat_binds, path_ident, render, starts_before, strip, strip_pat, visit_locals,
+};
+use crate::walk::FnItem;
+
+const ID: &str = "unvalidated-pda";
+const SEVERITY: Severity = Severity::High;
+
+// how many `let` bindings we follow back when looking for where a bump came from
+const MAX_LOCAL_DEPTH: usize = 4;
+
+pub struct PdaValidationDetector;
+
+impl Detector for PdaValidationDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "PDA derived with a caller-controlled bump, or a PDA account never re-derived and compared against the passed-in key."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions.iter().flat_map(check_pda_validation).collect()
+    }
+}
+
+// Where the bump of a `create_program_address` call comes from
+enum BumpSource {
+    Canonical,
+    InstructionData(String),
+    AccountData(String),
+    Unknown,
+}
+
+fn check_pda_validation(func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let mut derivations = Vec::new();
+    let cfg = Cfg::new(func.block);
+
+    for_each_expr(func.block, |expr| {
+        if is_pda_derivation_call(expr) {
+            derivations.push(expr);
+        }
+    });
+
+    for &call in &derivations {
+        let seeds = call_args(call).first().map(|seeds| seed_list(func, seeds, call)).unwrap_or_default();
+        let seeds_text = seeds.iter().map(|seed| render(seed)).collect::<Vec<_>>().join(", ");
+
+        if is_create_program_address(call) {
+            let bump = seeds.last().map(|seed| bump_expr(seed));
+            let source = bump.map_or(BumpSource::Unknown, |bump| bump_source(func, bump, call, 0));
+            let (origin, confidence) = match &source {
+                BumpSource::InstructionData(name) => (format!("instruction data ('{}')", name), Confidence::High),
+                BumpSource::AccountData(name) => (format!("account data ('{}')", name), Confidence::Low),
+                BumpSource::Canonical | BumpSource::Unknown => (String::new(), Confidence::Low),
+            };
+            if let (Some(bump), false) = (bump, origin.is_empty()) {
+                issues.push(
+                    func.finding(
+                        ID,
+                        SEVERITY,
+                        call.span(),
+                        format!(
+                            "{} '{}' derives a PDA with `create_program_address` from seeds [{}] using bump '{}' taken from {}, so it may not be the canonical bump.",
+                            func.kind_label(),
+                            func.name(),
+                            seeds_text,
+                            render(bump),
+                            origin
+                        ),
+                    )
+                    .with_confidence(confidence)
+                    .with_remediation(
+                        "Derive the address with `Pubkey::find_program_address`, or check the supplied bump against the canonical one stored at initialization.",
+                    ),
+                );
+            }
+        }
+
+        if is_used_as_address(func, call) && !is_compared_to_account_key(func, &cfg, call) {
+            issues.push(
+                func.finding(
+                    ID,
+                    SEVERITY,
+                    call.span(),
+                    format!(
+                        "{} '{}' derives a PDA from seeds [{}] but does not check that it equals the key of the account that was passed in before using it.",
+                        func.kind_label(),
+                        func.name(),
+                        seeds_text
+                    ),
+                )
+                .with_confidence(Confidence::Low)
+                .with_remediation(
+                    "Return `ProgramError::InvalidSeeds` when the derived address differs from the account's key.",
+                ),
+            );
+        }
+    }
+
+    // accounts the program keeps a bump in are its PDAs, and have to be re-derived before
+    // they are trusted. a bump that goes into the seeds of a derivation is covered above
+    for (account, read) in stored_bump_reads(func) {
+        let feeds_derivation = derivations.iter().any(|&call| {
+            call_args(call)
+                .first()
+                .is_some_and(|seeds| seed_list(func, seeds, call).iter().any(|seed| contains_expr(seed, |inner| ptr::eq(inner, read))))
+        });
+        let validated = derivations
+            .iter()
+            .any(|&call| validated_account(func, &cfg, call).as_deref() == Some(account.as_str()));
+        if !feeds_derivation && !validated {
+            issues.push(
+                func.finding(
+                    ID,
+                    SEVERITY,
+                    read.span(),
+                    format!(
+                        "{} '{}' reads the bump stored in account '{}' but trusts it as a PDA without re-deriving it from its seeds and comparing the key.",
+                        func.kind_label(),
+                        func.name(),
+                        account
+                    ),
+                )
+                .with_confidence(Confidence::Low)
+                .with_remediation(&format!(
+                    "Re-derive the address with `Pubkey::create_program_address` and the stored bump, and compare it against `{}.key`.",
+                    account
+                )),
+            );
+        }
+    }
+
+    issues
+}
+
+fn is_pda_derivation_call(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(call) => {
+            if let Expr::Path(expr_path) = &*call.func {
+                let func_name = expr_path.path.segments.last().unwrap().ident.to_string();
+                let derivation_functions = [
+                    "create_program_address",
+                    "try_create_program_address",
+                    "find_program_address",
+                    "try_find_program_address",
+                ];
+                derivation_functions.contains(&func_name.as_str())
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+fn is_create_program_address(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(call) => match &*call.func {
+            Expr::Path(expr_path) => expr_path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident.to_string().ends_with("create_program_address")),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// The seed expressions of `&[b"vault", user.key.as_ref(), &[bump]]`, following a `seeds` local
+fn seed_list<'a>(func: &FnItem<'a>, seeds: &'a Expr, at: &Expr) -> Vec<&'a Expr> {
+    let seeds = strip(seeds);
+    if let Expr::Path(expr_path) = seeds {
+        let init = expr_path
+            .path
+            .get_ident()
+            .and_then(|ident| local_init(func.block, &ident.to_string(), at));
+        if let Some(init) = init {
+            return seed_list(func, init, at);
+        }
+    }
+    match seeds {
+        Expr::Array(expr_array) => expr_array.elems.iter().collect(),
+        Expr::MethodCall(method_call) if method_call.method == "as_ref" => seed_list(func, &method_call.receiver, at),
+        _ => Vec::new(),
+    }
+}
+
+// `bump` out of `&[bump]`, `&[bump][..]` or `bump.to_le_bytes().as_ref()`
+fn bump_expr(seed: &Expr) -> &Expr {
+    match strip(seed) {
+        Expr::Array(expr_array) if expr_array.elems.len() == 1 => strip(&expr_array.elems[0]),
+        Expr::Index(expr_index) => bump_expr(&expr_index.expr),
+        Expr::MethodCall(method_call) => bump_expr(&method_call.receiver),
+        expr => expr,
+    }
+}
+
+fn bump_source(func: &FnItem, expr: &Expr, at: &Expr, depth: usize) -> BumpSource {
+    if contains_expr(expr, |expr| {
+        call_name(expr).is_some_and(|name| name.ends_with("find_program_address"))
+    }) {
+        return BumpSource::Canonical;
+    }
+    // `vault.data.borrow()[0]`, `vault.try_borrow_data()?` or Anchor's `ctx.accounts.vault.bump`
+    if contains_expr(expr, is_account_data_read) {
+        return BumpSource::AccountData(render(expr));
+    }
+
+    let mut idents = Vec::new();
+    contains_expr(expr, |expr| {
+        if let Expr::Path(expr_path) = expr {
+            if let Some(ident) = expr_path.path.get_ident() {
+                idents.push(ident.to_string());
+            }
+        }
+        false
+    });
+
+    let mut source = BumpSource::Unknown;
+    for ident in idents {
+        if is_instruction_param(func, &ident) {
+            return BumpSource::InstructionData(ident);
+        }
+        if depth >= MAX_LOCAL_DEPTH {
+            continue;
+        }
+        let Some(init) = binding_init(func, &ident, at) else {
+            continue;
+        };
+        match bump_source(func, init, at, depth + 1) {
+            BumpSource::InstructionData(param) => return BumpSource::InstructionData(param),
+            BumpSource::AccountData(_) => source = BumpSource::AccountData(ident),
+            BumpSource::Canonical => {
+                if matches!(source, BumpSource::Unknown) {
+                    source = BumpSource::Canonical;
+                }
+            }
+            BumpSource::Unknown => {}
+        }
+    }
+    source
+}
+
+// Initializer of the last `let` that binds `name`, tuples included, e.g. `let (pda, bump) = ..`
+fn binding_init<'a>(func: &FnItem<'a>, name: &str, at: &Expr) -> Option<&'a Expr> {
+    let mut init = None;
+    visit_locals(func.block, |local| {
+        if let Some(local_init) = &local.init {
+            if pat_binds(&local.pat, name) && starts_before(local, at) {
+                init = Some(&*local_init.expr);
+            }
+        }
+    });
+    init
+}
+
+fn is_account_data_read(expr: &Expr) -> bool {
+    match expr {
+        Expr::Field(expr_field) => {
+            let member = member_name(&expr_field.member);
+            member == "data"
+                || matches!(strip(&expr_field.base), Expr::Field(inner) if member_name(&inner.member) == "accounts")
+        }
+        Expr::MethodCall(method_call) => {
+            matches!(method_call.method.to_string().as_str(), "try_borrow_data" | "try_borrow_mut_data")
+        }
+        _ => false,
+    }
+}
+
+// A parameter holding instruction data: anything that is not an account, the
+// accounts slice, an Anchor `Context` or the program id
+pub(crate) fn is_instruction_param(func: &FnItem, name: &str) -> bool {
+    func.sig.inputs.iter().any(|input| match input {
+        FnArg::Typed(pat_type) => {
+            matches!(strip_pat(&pat_type.pat), Pat::Ident(pat_ident) if pat_ident.ident == name)
+                && !is_account_info_type(&pat_type.ty)
+                && !is_context_type(&pat_type.ty)
+        }
+        FnArg::Receiver(_) => false,
+    })
+}
+
+fn is_context_type(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => is_context_type(&reference.elem),
+        Type::Slice(slice) => is_account_info_type(&slice.elem),
+        Type::Path(type_path) => type_path.path.segments.last().is_some_and(|segment| {
+            ["Context", "Pubkey"].contains(&segment.ident.to_string().as_str())
+        }),
+        _ => false,
+    }
+}
+
+// Names the derived address is bound to, `None` when the call is not the initializer of a
+// `let`, e.g. `pda` for `let (pda, bump) = Pubkey::find_program_address(..)`
+fn address_bindings(func: &FnItem, call: &Expr) -> Option<Vec<String>> {
+    let mut names = None;
+    visit_locals(func.block, |local| {
+        if let Some(init) = &local.init {
+            if contains_expr(&init.expr, |expr| ptr::eq(expr, call)) {
+                let names = names.get_or_insert_with(Vec::new);
+                match strip_pat(&local.pat) {
+                    Pat::Tuple(pat_tuple) => {
+                        if let Some(Pat::Ident(pat_ident)) = pat_tuple.elems.first().map(strip_pat) {
+                            names.push(pat_ident.ident.to_string());
+                        }
+                    }
+                    Pat::Ident(pat_ident) => names.push(pat_ident.ident.to_string()),
+                    _ => {}
+                }
+            }
+        }
+    });
+    names
+}
+
+// The derived address stands in for an account: it is passed on, as in `AccountMeta::new(pda, false)`,
+// or put into a struct. a derivation kept only for its bump, e.g. for the signer seeds of
+// `invoke_signed`, does not
+fn is_used_as_address(func: &FnItem, call: &Expr) -> bool {
+    match address_bindings(func, call) {
+        Some(names) => !address_uses(func, &names).is_empty(),
+        None => true,
+    }
+}
+
+// The arguments and struct fields the derived address is passed as
+fn address_uses<'a>(func: &FnItem<'a>, names: &[String]) -> Vec<&'a Expr> {
+    let mut uses = Vec::new();
+    for_each_expr(func.block, |expr| {
+        let values: Vec<&Expr> = match expr {
+            Expr::Call(expr_call) => expr_call.args.iter().collect(),
+            Expr::MethodCall(method_call) => method_call.args.iter().collect(),
+            Expr::Struct(expr_struct) => expr_struct.fields.iter().map(|field| &field.expr).collect(),
+            _ => Vec::new(),
+        };
+        uses.extend(
+            values
+                .into_iter()
+                .filter(|value| path_ident(value).is_some_and(|name| names.contains(&name))),
+        );
+    });
+    uses
+}
+
+// The derived address is known to equal some account key wherever it is used, either
+// directly (`if create_program_address(..)? != *vault.key { return Err(..) }`) or through the
+// local it was bound to. an address that is not passed on has to be checked on every path
+// that returns normally
+fn is_compared_to_account_key(func: &FnItem, cfg: &Cfg, call: &Expr) -> bool {
+    let names = address_bindings(func, call).unwrap_or_default();
+    let uses = address_uses(func, &names);
+    if uses.is_empty() {
+        return validated_account(func, cfg, call).is_some();
+    }
+    uses.iter().all(|&use_expr| {
+        cfg.facts_of(use_expr)
+            .into_iter()
+            .any(|(fact, holds)| pinned_account(call, &names, fact, holds).is_some())
+    })
+}
+
+// The account the derived address is checked against by a branch every normal return goes through
+fn validated_account(func: &FnItem, cfg: &Cfg, call: &Expr) -> Option<String> {
+    let names = address_bindings(func, call).unwrap_or_default();
+    cfg.branches().find_map(|(cond, holds, start)| {
+        let mut account = None;
+        for_each_fact(cond, holds, &mut |fact, holds| {
+            account = account.take().or_else(|| pinned_account(call, &names, fact, holds));
+        });
+        account.filter(|_| cfg.dominates_returns(start))
+    })
+}
+
+// The account whose key `fact` evaluating to `holds` makes the derived address equal to:
+// `pda == *vault.key` holding or `pda != *vault.key` failing
+fn pinned_account(call: &Expr, names: &[String], fact: &Expr, holds: bool) -> Option<String> {
+    let is_derived = |expr: &Expr| {
+        contains_expr(expr, |inner| ptr::eq(inner, call)) || path_ident(expr).is_some_and(|name| names.contains(&name))
+    };
+    let (left, right, true) = equality(fact, holds)? else {
+        return None;
+    };
+    if is_derived(left) {
+        key_owner(right)
+    } else if is_derived(right) {
+        key_owner(left)
+    } else {
+        None
+    }
+}
+
+// `state.bump` reads where `state` was deserialized from an account, with that account
+fn stored_bump_reads<'a>(func: &FnItem<'a>) -> Vec<(String, &'a Expr)> {
+    let mut reads: Vec<(String, &Expr)> = Vec::new();
+    for_each_expr(func.block, |expr| {
+        let Expr::Field(expr_field) = expr else {
+            return;
+        };
+        if member_name(&expr_field.member) != "bump" {
+            return;
+        }
+        let account = path_ident(&expr_field.base)
+            .and_then(|state| local_init(func.block, &state, expr))
+            .filter(|init| contains_expr(init, is_deserialization_call))
+            .and_then(|init| unpacked_from(func, init, expr));
+        if let Some(account) = account {
+            if !reads.iter().any(|(seen, _)| *seen == account) {
+                reads.push((account, expr));
+            }
+        }
+    });
+    reads
+}
@@ -0,0 +1,245 @@
+use syn::spanned::Spanned;
+use syn::{BinOp, Expr, Lit, Pat, Type};
+
+use super::arithmetic::is_amount_name;
+use super::slippage::{is_actual_amount_expr, is_expected_amount_expr};
+use super::{AnalysisContext, Detector};
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{contains_expr, for_each_expr, member_name, render, strip, strip_pat, visit_locals};
+use crate::walk::FnItem;
+
+const ID: &str = "precision-loss";
+const SEVERITY: Severity = Severity::Medium;
+
+// handlers where the user hands value back and should be charged the rounded-up amount
+const PAYING_PATHS: [&str; 4] = ["withdraw", "redeem", "burn", "repay"];
+// handlers where the user receives value and should get the rounded-down amount
+const RECEIVING_PATHS: [&str; 4] = ["mint", "deposit", "stake", "borrow"];
+
+const CEIL_DIVISIONS: [&str; 4] = ["div_ceil", "checked_div_ceil", "checked_ceil_div", "ceil_div"];
+
+pub struct PrecisionLossDetector;
+
+impl Detector for PrecisionLossDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Division before multiplication, rounding that favours the user in withdraw or mint paths, and narrowing casts on amounts."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions.iter().flat_map(check_precision_loss).collect()
+    }
+}
+
+fn check_precision_loss(func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+
+    for_each_expr(func.block, |expr| {
+        if let Some(division) = division_before_multiplication(expr) {
+            issues.push(
+                func.finding(
+                    ID,
+                    SEVERITY,
+                    expr.span(),
+                    format!(
+                        "{} '{}' multiplies the result of `{}`, losing the remainder of the division before it is scaled back up.",
+                        func.kind_label(),
+                        func.name(),
+                        render(division)
+                    ),
+                )
+                .with_confidence(Confidence::Medium)
+                .with_remediation("Multiply first, in `u128` if needed, and divide last."),
+            );
+        }
+
+        if let Some(target) = narrowing_cast(expr) {
+            issues.push(
+                func.finding(
+                    ID,
+                    SEVERITY,
+                    expr.span(),
+                    format!(
+                        "{} '{}' narrows an amount with `{}`, which silently truncates values that do not fit in {}.",
+                        func.kind_label(),
+                        func.name(),
+                        render(expr),
+                        target
+                    ),
+                )
+                .with_confidence(Confidence::Medium)
+                .with_remediation(&format!(
+                    "Convert with `{}::try_from(..)` and return an error when the value does not fit.",
+                    target
+                )),
+            );
+        }
+    });
+
+    issues.extend(check_rounding_direction(func));
+    issues
+}
+
+// The division inside `amount / total * shares`, `(a / b) * c` or
+// `a.checked_div(b)?.checked_mul(c)`
+fn division_before_multiplication(expr: &Expr) -> Option<&Expr> {
+    match expr {
+        Expr::Binary(expr_binary) if matches!(expr_binary.op, BinOp::Mul(_) | BinOp::MulAssign(_)) => {
+            [&*expr_binary.left, &*expr_binary.right]
+                .into_iter()
+                .map(through_cast)
+                .find(|side| is_division(side))
+        }
+        Expr::MethodCall(method_call)
+            if matches!(method_call.method.to_string().as_str(), "checked_mul" | "saturating_mul" | "mul") =>
+        {
+            let receiver = through_unwrap(&method_call.receiver);
+            is_division(receiver).then_some(receiver)
+        }
+        _ => None,
+    }
+}
+
+fn is_division(expr: &Expr) -> bool {
+    match expr {
+        Expr::Binary(expr_binary) => matches!(expr_binary.op, BinOp::Div(_)),
+        Expr::MethodCall(method_call) => method_call.method == "checked_div",
+        _ => false,
+    }
+}
+
+// `x` out of `(x as u128)` and friends
+fn through_cast(expr: &Expr) -> &Expr {
+    match strip(expr) {
+        Expr::Cast(expr_cast) => through_cast(&expr_cast.expr),
+        expr => expr,
+    }
+}
+
+// `x` out of `x?`, `x.unwrap()`, `x.ok_or(..)?` or `x.expect(..)`
+fn through_unwrap(expr: &Expr) -> &Expr {
+    match strip(expr) {
+        Expr::MethodCall(method_call)
+            if matches!(method_call.method.to_string().as_str(), "unwrap" | "expect" | "ok_or" | "ok_or_else") =>
+        {
+            through_unwrap(&method_call.receiver)
+        }
+        expr => expr,
+    }
+}
+
+// The target type of `(amount * price / PRECISION) as u64` when the value is amount-like
+// and the cast can lose bits: any cast to 32 bits or fewer, or to 64 bits out of u128 math
+fn narrowing_cast(expr: &Expr) -> Option<String> {
+    let Expr::Cast(expr_cast) = expr else {
+        return None;
+    };
+    let Type::Path(type_path) = &*expr_cast.ty else {
+        return None;
+    };
+    let target = type_path.path.get_ident()?.to_string();
+    let narrow = match target.as_str() {
+        "u8" | "u16" | "u32" | "i8" | "i16" | "i32" => true,
+        "u64" | "i64" => contains_expr(&expr_cast.expr, mentions_u128),
+        _ => false,
+    };
+    (narrow && contains_expr(&expr_cast.expr, is_amount_like)).then_some(target)
+}
+
+fn is_amount_like(expr: &Expr) -> bool {
+    match expr {
+        Expr::Path(expr_path) => {
+            is_expected_amount_expr(expr)
+                || is_actual_amount_expr(expr)
+                || expr_path.path.get_ident().is_some_and(|ident| is_amount_name(&ident.to_string()))
+        }
+        Expr::Field(expr_field) => is_amount_name(&member_name(&expr_field.member)),
+        _ => false,
+    }
+}
+
+fn mentions_u128(expr: &Expr) -> bool {
+    match expr {
+        Expr::Cast(expr_cast) => {
+            matches!(&*expr_cast.ty, Type::Path(type_path)
+                if type_path.path.is_ident("u128") || type_path.path.is_ident("i128"))
+        }
+        Expr::Lit(expr_lit) => matches!(&expr_lit.lit, Lit::Int(lit_int) if lit_int.suffix().ends_with("128")),
+        _ => false,
+    }
+}
+
+// Shares burned on withdraw rounded down, or tokens minted on deposit rounded up, both
+// let the user keep the remainder. fees rounded down do the same anywhere
+fn check_rounding_direction(func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let name = func.name().to_lowercase();
+    let paying = PAYING_PATHS.iter().any(|path| name.contains(path));
+    let receiving = RECEIVING_PATHS.iter().any(|path| name.contains(path));
+
+    visit_locals(func.block, |local| {
+        let (Pat::Ident(pat_ident), Some(init)) = (strip_pat(&local.pat), &local.init) else {
+            return;
+        };
+        let binding = pat_ident.ident.to_string().to_lowercase();
+        let init = &*init.expr;
+        if !contains_expr(init, is_division) && !contains_expr(init, is_ceil_division) {
+            return;
+        }
+
+        let rounding = if contains_expr(init, is_ceil_division) {
+            (receiving && ["mint", "shares", "out", "reward"].iter().any(|part| binding.contains(part)))
+                .then_some("rounds up the amount the user receives")
+        } else if binding.contains("fee") {
+            Some("rounds the fee down")
+        } else {
+            (paying && ["burn", "shares", "cost", "repay"].iter().any(|part| binding.contains(part)))
+                .then_some("rounds down the amount the user pays")
+        };
+        let Some(rounding) = rounding else {
+            return;
+        };
+
+        issues.push(
+            func.finding(
+                ID,
+                SEVERITY,
+                local.span(),
+                format!(
+                    "{} '{}' computes '{}' with a division that {}, which favours the user over the protocol.",
+                    func.kind_label(),
+                    func.name(),
+                    pat_ident.ident,
+                    rounding
+                ),
+            )
+            .with_confidence(Confidence::Low)
+            .with_remediation("Round amounts the user pays up and amounts the user receives down."),
+        );
+    });
+
+    issues
+}
+
+fn is_one(expr: &Expr) -> bool {
+    matches!(strip(expr), Expr::Lit(expr_lit) if matches!(&expr_lit.lit, Lit::Int(lit_int) if lit_int.base10_digits() == "1"))
+}
+
+// `a.div_ceil(b)` or the `(a + b - 1) / b` idiom
+fn is_ceil_division(expr: &Expr) -> bool {
+    match expr {
+        Expr::MethodCall(method_call) => CEIL_DIVISIONS.contains(&method_call.method.to_string().as_str()),
+        Expr::Binary(expr_binary) if matches!(expr_binary.op, BinOp::Div(_)) => match strip(&expr_binary.left) {
+            Expr::Binary(numerator) => matches!(numerator.op, BinOp::Sub(_)) && is_one(&numerator.right),
+            _ => false,
+        },
+        _ => false,
+    }
+}
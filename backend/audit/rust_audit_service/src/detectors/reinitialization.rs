@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+
+use syn::spanned::Spanned;
+use syn::Expr;
+
+use super::access_control::{account_data_borrow_owner, collect_state_variables_stmt, is_serialize_method};
+use super::account_close::is_zero;
+use super::{AnalysisContext, Detector};
+use crate::cfg::{equality, ordering, Cfg};
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{
+    call_args, call_name, contains_expr, for_each_expr, key_owner, local_init, member_name, path_ident, root_ident,
+    strip,
+};
+use crate::walk::FnItem;
+
+const ID: &str = "account-reinitialization";
+const SEVERITY: Severity = Severity::High;
+
+// flags that are set once an account is initialized, and the tags of its type
+const INITIALIZED_FLAGS: [&str; 2] = ["is_initialized", "initialized"];
+const TYPE_TAGS: [&str; 2] = ["discriminator", "account_type"];
+
+pub struct ReinitializationDetector;
+
+impl Detector for ReinitializationDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Init-style handler writes fresh state into an account without checking `is_initialized`, a discriminator or an empty data length."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions.iter().flat_map(check_reinitialization).collect()
+    }
+}
+
+fn check_reinitialization(func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let name = func.name().to_lowercase();
+    let init_named = name.starts_with("init") || name.starts_with("process_init");
+
+    let cfg = Cfg::new(func.block);
+    let mut state_variables = HashSet::new();
+    for stmt in &func.block.stmts {
+        collect_state_variables_stmt(stmt, &mut state_variables);
+    }
+
+    let mut reported = Vec::new();
+    for_each_expr(func.block, |expr| {
+        let Some((state, fresh)) = state_write(func, expr, &state_variables) else {
+            return;
+        };
+        if !(fresh || init_named) {
+            return;
+        }
+        let account = call_args(expr)
+            .iter()
+            .find_map(|arg| written_account(func, arg, expr));
+        if reported.contains(&account) || creates_account(func, &cfg, account.as_deref(), expr) {
+            return;
+        }
+        let checked = cfg
+            .facts_of(expr)
+            .into_iter()
+            .any(|(fact, holds)| shows_uninitialized(func, fact, holds, account.as_deref(), expr));
+        if checked {
+            return;
+        }
+
+        let target = match &account {
+            Some(account) => format!("account '{}'", account),
+            None => "an account".to_string(),
+        };
+        issues.push(
+            func.finding(
+                ID,
+                SEVERITY,
+                expr.span(),
+                format!(
+                    "{} '{}' writes {} state '{}' into {} without checking that it is not already initialized, so the instruction can be replayed to overwrite it.",
+                    func.kind_label(),
+                    func.name(),
+                    if fresh { "newly constructed" } else { "initial" },
+                    state,
+                    target
+                ),
+            )
+            .with_confidence(if fresh && init_named { Confidence::High } else { Confidence::Medium })
+            .with_remediation(
+                "Return `ProgramError::AccountAlreadyInitialized` when the stored `is_initialized` flag or discriminator is set, or when the account data is not empty.",
+            ),
+        );
+        reported.push(account);
+    });
+
+    issues
+}
+
+// `state.serialize(..)`, `state.try_to_vec()` or `Vault::pack(state, ..)` where `state` is
+// deserialized account state or a struct literal. returns the state and whether it is a literal
+fn state_write(func: &FnItem, expr: &Expr, state_variables: &HashSet<String>) -> Option<(String, bool)> {
+    let state = match expr {
+        Expr::MethodCall(method_call) if is_serialize_method(&method_call.method.to_string()) => &*method_call.receiver,
+        Expr::Call(_) if matches!(call_name(expr).as_deref(), Some("pack" | "pack_into_slice")) => {
+            call_args(expr).first().copied()?
+        }
+        _ => return None,
+    };
+
+    let name = root_ident(state).unwrap_or_else(|| "<literal>".to_string());
+    let resolved = match path_ident(state) {
+        Some(ident) => local_init(func.block, &ident, expr).map(strip).unwrap_or(strip(state)),
+        None => strip(state),
+    };
+    if let Expr::Struct(expr_struct) = resolved {
+        let type_name = expr_struct
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or(name);
+        return Some((type_name, true));
+    }
+    state_variables.contains(&name).then_some((name, false))
+}
+
+// The account whose data a write lands in, also through `let mut data = vault.data.borrow_mut();`
+fn written_account(func: &FnItem, arg: &Expr, at: &Expr) -> Option<String> {
+    let mut owner = None;
+    contains_expr(arg, |inner| {
+        owner = account_data_borrow_owner(inner);
+        owner.is_some()
+    });
+    owner.or_else(|| {
+        let init = local_init(func.block, &path_ident(arg)?, at)?;
+        let mut owner = None;
+        contains_expr(init, |inner| {
+            owner = account_data_borrow_owner(inner);
+            owner.is_some()
+        });
+        owner
+    })
+}
+
+// Whether `fact` evaluating to `holds` means the written account is not initialized yet:
+// `state.is_initialized` failing, `vault.data_is_empty()` holding, `vault.data_len() == 0`,
+// or a discriminator equal to zero or unequal to the tag of an initialized account
+fn shows_uninitialized(func: &FnItem, fact: &Expr, holds: bool, account: Option<&str>, at: &Expr) -> bool {
+    let describes = |root: Option<String>| root.is_some_and(|root| describes_account(func, &root, account, at));
+    match strip(fact) {
+        Expr::Field(expr_field) if INITIALIZED_FLAGS.contains(&member_name(&expr_field.member).as_str()) => {
+            !holds && describes(root_ident(&expr_field.base))
+        }
+        Expr::MethodCall(method_call) if method_call.method == "data_is_empty" => {
+            holds && describes(root_ident(&method_call.receiver))
+        }
+        _ => {
+            if let Some((greater, smaller, _)) = ordering(fact, holds) {
+                return is_zero(greater) && describes(data_len_owner(smaller));
+            }
+            let Some((left, right, equal)) = equality(fact, holds) else {
+                return false;
+            };
+            [(left, right), (right, left)].into_iter().any(|(side, value)| {
+                if let Some(owner) = data_len_owner(side) {
+                    return equal && is_zero(value) && describes(Some(owner));
+                }
+                match strip(side) {
+                    Expr::Field(expr_field) if TYPE_TAGS.contains(&member_name(&expr_field.member).as_str()) => {
+                        equal == is_uninitialized_tag(value) && describes(root_ident(&expr_field.base))
+                    }
+                    _ => false,
+                }
+            })
+        }
+    }
+}
+
+// True when `root` is the written account or state deserialized from its data. any
+// state counts when the write does not say which account it lands in
+fn describes_account(func: &FnItem, root: &str, account: Option<&str>, at: &Expr) -> bool {
+    let Some(account) = account else {
+        return true;
+    };
+    root == account
+        || local_init(func.block, root, at).is_some_and(|init| {
+            contains_expr(init, |inner| account_data_borrow_owner(inner).as_deref() == Some(account))
+        })
+}
+
+// `0`, `[0; 8]` or `AccountType::Uninitialized`
+fn is_uninitialized_tag(expr: &Expr) -> bool {
+    match strip(expr) {
+        Expr::Repeat(expr_repeat) => is_zero(&expr_repeat.expr),
+        Expr::Path(expr_path) => expr_path
+            .path
+            .segments
+            .iter()
+            .any(|segment| segment.ident.to_string().contains("Uninitialized")),
+        expr => is_zero(expr),
+    }
+}
+
+// `vault` for `vault.data_len()` or `vault.data.borrow().len()`
+fn data_len_owner(expr: &Expr) -> Option<String> {
+    match strip(expr) {
+        Expr::MethodCall(method_call) if method_call.method == "data_len" => root_ident(&method_call.receiver),
+        Expr::MethodCall(method_call) if method_call.method == "len" => {
+            let mut owner = None;
+            contains_expr(&method_call.receiver, |inner| {
+                owner = account_data_borrow_owner(inner);
+                owner.is_some()
+            });
+            owner
+        }
+        _ => None,
+    }
+}
+
+// `system_instruction::create_account` fails on an account that already holds lamports,
+// so a write into the account it just created cannot be replayed
+fn creates_account(func: &FnItem, cfg: &Cfg, account: Option<&str>, at: &Expr) -> bool {
+    let Some(account) = account else {
+        return false;
+    };
+    let mut creates = false;
+    for_each_expr(func.block, |expr| {
+        if matches!(call_name(expr).as_deref(), Some("create_account" | "create_account_with_seed"))
+            && call_args(expr).get(1).and_then(|to| key_owner(to)).as_deref() == Some(account)
+            && cfg.runs_before(expr, at)
+        {
+            creates = true;
+        }
+    });
+    creates
+}
@@ -0,0 +1,261 @@
+use syn::spanned::Spanned;
+use syn::{Block, Expr, ExprMethodCall, Stmt};
+
+use super::{AnalysisContext, Detector};
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::strip;
+
+const ID: &str = "missing-rent-exemption-check";
+const SEVERITY: Severity = Severity::Low;
+
+pub struct RentExemptionDetector;
+
+impl Detector for RentExemptionDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Account created, assigned or allocated without a `rent.is_exempt(...)` check."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut issues = Vec::new();
+
+        for func in &ctx.functions {
+            if creates_new_account(func.block) && !has_rent_exemption_check(func.block) {
+                issues.push(
+                    func.finding(
+                        ID,
+                        SEVERITY,
+                        func.sig.span(),
+                        format!(
+                            "{} '{}' creates a new account without checking for rent exemption.",
+                            func.kind_label(),
+                            func.name()
+                        ),
+                    )
+                    .with_confidence(Confidence::High)
+                    .with_remediation(
+                        "Fund new accounts with `Rent::minimum_balance` and verify them with `rent.is_exempt(...)`.",
+                    ),
+                );
+            }
+        }
+
+        issues
+    }
+}
+
+fn creates_new_account(block: &Block) -> bool {
+    block.stmts.iter().any(contains_account_creation)
+}
+
+fn has_rent_exemption_check(block: &Block) -> bool {
+    block
+        .stmts
+        .iter()
+        .any(contains_rent_exemption_check)
+}
+
+fn contains_account_creation(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Expr(expr, _) => expr_contains_account_creation(expr),
+        Stmt::Local(local) => {
+            if let Some(init) = &local.init {
+                expr_contains_account_creation(&init.expr)
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+fn contains_rent_exemption_check(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Expr(expr, _) => expr_contains_rent_exemption_check(expr),
+        Stmt::Local(local) => {
+            if let Some(init) = &local.init {
+                expr_contains_rent_exemption_check(&init.expr)
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+// here im recursively checking if an expression contains account creation with common methods in contracts args also
+fn expr_contains_account_creation(expr: &Expr) -> bool {
+    match expr {
+        Expr::MethodCall(method_call) => {
+            let method_name = method_call.method.to_string().to_lowercase();
+            let account_creation_methods = [
+                "create_account",
+                "create_account_with_seed",
+                "create_program_account",
+                "new_account",
+                "new_account_with_seed",
+                "assign",
+                "allocate",
+            ];
+            if account_creation_methods.contains(&method_name.as_str()) && !is_returned_to_system_program(method_call) {
+                return true;
+            }
+            method_call
+                .args
+                .iter()
+                .any(expr_contains_account_creation)
+        }
+        Expr::Call(call) => {
+            if let Expr::Path(expr_path) = &*call.func {
+                let func_name = expr_path
+                    .path
+                    .segments
+                    .last()
+                    .unwrap()
+                    .ident
+                    .to_string()
+                    .to_lowercase();
+                let account_creation_functions = [
+                    "create_account",
+                    "create_account_with_seed",
+                    "create_program_account",
+                    "new_account",
+                    "new_account_with_seed",
+                    "assign",
+                    "allocate",
+                ];
+                if account_creation_functions.contains(&func_name.as_str()) {
+                    return true;
+                }
+            }
+            call.args
+                .iter()
+                .any(expr_contains_account_creation)
+        }
+        Expr::Block(expr_block) => {
+            expr_block
+                .block
+                .stmts
+                .iter()
+                .any(contains_account_creation)
+        }
+        Expr::If(expr_if) => {
+            expr_contains_account_creation(&expr_if.cond)
+                || expr_if
+                    .then_branch
+                    .stmts
+                    .iter()
+                    .any(contains_account_creation)
+                || expr_if
+                    .else_branch
+                    .as_ref()
+                    .is_some_and(|(_, else_expr)| expr_contains_account_creation(else_expr))
+        }
+        Expr::Match(expr_match) => {
+            expr_contains_account_creation(&expr_match.expr)
+                || expr_match
+                    .arms
+                    .iter()
+                    .any(|arm| expr_contains_account_creation(&arm.body))
+        }
+        Expr::Await(expr_await) => expr_contains_account_creation(&expr_await.base),
+        Expr::Try(expr_try) => expr_contains_account_creation(&expr_try.expr),
+        Expr::Paren(expr_paren) => expr_contains_account_creation(&expr_paren.expr),
+        Expr::Unary(expr_unary) => expr_contains_account_creation(&expr_unary.expr),
+        Expr::Binary(expr_binary) => {
+            expr_contains_account_creation(&expr_binary.left)
+                || expr_contains_account_creation(&expr_binary.right)
+        }
+        _ => false,
+    }
+}
+
+// `vault.assign(&system_program::ID)` hands a closed account back, it creates nothing
+pub(crate) fn is_returned_to_system_program(method_call: &ExprMethodCall) -> bool {
+    if method_call.method != "assign" {
+        return false;
+    }
+    let owner = match method_call.args.first().map(strip) {
+        Some(Expr::Path(expr_path)) => &expr_path.path,
+        Some(Expr::Call(call)) => match &*call.func {
+            Expr::Path(expr_path) => &expr_path.path,
+            _ => return false,
+        },
+        _ => return false,
+    };
+    owner.segments.iter().any(|segment| segment.ident == "system_program")
+}
+
+// saame as expression
+fn expr_contains_rent_exemption_check(expr: &Expr) -> bool {
+    match expr {
+        Expr::MethodCall(method_call) => {
+            if let Expr::Path(expr_path) = &*method_call.receiver {
+                let receiver_name = expr_path
+                    .path
+                    .segments
+                    .last()
+                    .unwrap()
+                    .ident
+                    .to_string()
+                    .to_lowercase();
+                let method_name = method_call.method.to_string().to_lowercase();
+
+                if receiver_name == "rent" && method_name == "is_exempt" {
+                    return true;
+                }
+            }
+            method_call
+                .args
+                .iter()
+                .any(expr_contains_rent_exemption_check)
+        }
+        Expr::Call(call) => {
+            call.args
+                .iter()
+                .any(expr_contains_rent_exemption_check)
+        }
+        Expr::If(expr_if) => {
+            expr_contains_rent_exemption_check(&expr_if.cond)
+                || expr_if
+                    .then_branch
+                    .stmts
+                    .iter()
+                    .any(contains_rent_exemption_check)
+                || expr_if
+                    .else_branch
+                    .as_ref()
+                    .is_some_and(|(_, else_expr)| expr_contains_rent_exemption_check(else_expr))
+        }
+        Expr::Block(expr_block) => {
+            expr_block
+                .block
+                .stmts
+                .iter()
+                .any(contains_rent_exemption_check)
+        }
+        Expr::Match(expr_match) => {
+            expr_contains_rent_exemption_check(&expr_match.expr)
+                || expr_match
+                    .arms
+                    .iter()
+                    .any(|arm| expr_contains_rent_exemption_check(&arm.body))
+        }
+        Expr::Await(expr_await) => expr_contains_rent_exemption_check(&expr_await.base),
+        Expr::Try(expr_try) => expr_contains_rent_exemption_check(&expr_try.expr),
+        Expr::Paren(expr_paren) => expr_contains_rent_exemption_check(&expr_paren.expr),
+        Expr::Unary(expr_unary) => expr_contains_rent_exemption_check(&expr_unary.expr),
+        Expr::Binary(expr_binary) => {
+            expr_contains_rent_exemption_check(&expr_binary.left)
+                || expr_contains_rent_exemption_check(&expr_binary.right)
+        }
+        _ => false,
+    }
+}
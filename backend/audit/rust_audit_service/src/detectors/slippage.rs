@@ -0,0 +1,268 @@
+use syn::spanned::Spanned;
+use syn::{BinOp, Block, Expr, ExprBinary, Stmt};
+
+use super::{AnalysisContext, Detector, Sink};
+use crate::cfg::Cfg;
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::for_each_expr;
+use crate::walk::FnItem;
+
+const ID: &str = "missing-slippage-check";
+const SEVERITY: Severity = Severity::Medium;
+
+pub struct SlippageDetector;
+
+impl Detector for SlippageDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Swap, transfer, mint or burn performed before the output amount is compared against a caller-supplied minimum."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions
+            .iter()
+            .filter_map(|func| check_slippage_checks(ctx, func))
+            .collect()
+    }
+}
+
+fn check_slippage_checks(ctx: &AnalysisContext, func: &FnItem) -> Option<Finding> {
+    let block = func.block;
+    // a call to a helper of the program that swaps further down counts as a swap
+    let swaps_through = |expr: &Expr| {
+        ctx.call_graph
+            .summary(expr)
+            .is_some_and(|summary| summary.sinks.contains(&Sink::Swap))
+    };
+
+    let mut swaps = Vec::new();
+    let mut through_helper = false;
+    for_each_expr(block, |expr| {
+        if swaps_through(expr) {
+            through_helper = true;
+            swaps.push(expr);
+        } else if is_swap_method_call(expr) {
+            swaps.push(expr);
+        }
+    });
+    if !is_swap_like_function(block) && !through_helper {
+        return None;
+    }
+
+    let cfg = Cfg::new(block);
+    // the check has to sit on every path to the swap and exit the function when it fails
+    let unchecked_swap = swaps
+        .into_iter()
+        .find(|swap| !cfg.guards_of(*swap).into_iter().any(is_slippage_condition))?;
+    Some(
+        func.finding(
+            ID,
+            SEVERITY,
+            unchecked_swap.span(),
+            format!(
+                "{} '{}' performs a swap operation without a slippage check.",
+                func.kind_label(),
+                func.name()
+            ),
+        )
+        .with_confidence(Confidence::Low)
+        .with_remediation(
+            "Compare the actual output amount against a caller-supplied minimum before transferring funds.",
+        ),
+    )
+}
+
+fn is_swap_like_function(block: &Block) -> bool {
+    block
+        .stmts
+        .iter()
+        .any(contains_swap_operation)
+}
+
+fn contains_swap_operation(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Expr(expr, _) => {
+            if is_swap_method_call(expr) {
+                true
+            } else {
+                expr_contains_swap(expr)
+            }
+        }
+        Stmt::Local(local) => {
+            if let Some(init) = &local.init {
+                expr_contains_swap(&init.expr)
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+fn expr_contains_swap(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(_) | Expr::MethodCall(_) => is_swap_method_call(expr),
+        Expr::Block(expr_block) => block_contains_swap(&expr_block.block),
+        Expr::If(expr_if) => {
+            block_contains_swap(&expr_if.then_branch)
+                || expr_if
+                    .else_branch
+                    .as_ref()
+                    .is_some_and(|(_, else_expr)| match else_expr.as_ref() {
+                        Expr::Block(block) => block_contains_swap(&block.block),
+                        _ => expr_contains_swap(else_expr),
+                    })
+        }
+        Expr::Match(expr_match) => {
+            expr_match
+                .arms
+                .iter()
+                .any(|arm| expr_contains_swap(&arm.body))
+        }
+        Expr::While(expr_while) => block_contains_swap(&expr_while.body),
+        Expr::ForLoop(expr_for) => block_contains_swap(&expr_for.body),
+        Expr::Paren(expr_paren) => expr_contains_swap(&expr_paren.expr),
+        Expr::Try(expr_try) => expr_contains_swap(&expr_try.expr),
+        Expr::Await(expr_await) => expr_contains_swap(&expr_await.base),
+        Expr::Unary(expr_unary) => expr_contains_swap(&expr_unary.expr),
+        Expr::Binary(expr_binary) => {
+            expr_contains_swap(&expr_binary.left) || expr_contains_swap(&expr_binary.right)
+        }
+        _ => false,
+    }
+}
+
+fn block_contains_swap(block: &Block) -> bool {
+    block
+        .stmts
+        .iter()
+        .any(contains_swap_operation)
+}
+
+fn is_slippage_condition(expr: &Expr) -> bool {
+    match expr {
+        Expr::Binary(ExprBinary {
+            left,
+            op: BinOp::Lt(_) | BinOp::Le(_) | BinOp::Eq(_) | BinOp::Ne(_) | BinOp::Gt(_) | BinOp::Ge(_),
+            right,
+            ..
+        }) => {
+            (is_expected_amount_expr(left) && is_actual_amount_expr(right))
+                || (is_expected_amount_expr(right) && is_actual_amount_expr(left))
+        }
+        // `amount_out < min_amount_out || pool_paused`
+        Expr::Binary(ExprBinary {
+            left,
+            op: BinOp::Or(_) | BinOp::And(_),
+            right,
+            ..
+        }) => is_slippage_condition(left) || is_slippage_condition(right),
+        Expr::Paren(expr_paren) => is_slippage_condition(&expr_paren.expr),
+        Expr::Unary(expr_unary) => is_slippage_condition(&expr_unary.expr),
+        _ => false,
+    }
+}
+
+pub(crate) fn is_swap_method_call(expr: &Expr) -> bool {
+    match expr {
+        Expr::MethodCall(method_call) => {
+            let method_name = method_call.method.to_string().to_lowercase();
+            let swap_methods = vec![
+                "transfer",
+                "transfer_from",
+                "swap",
+                "deposit",
+                "withdraw",
+                "exchange",
+                "buy",
+                "sell",
+                "send",
+                "receive",
+                "trade",
+                "mint",
+                "burn",
+            ];
+            swap_methods.contains(&method_name.as_str())
+        }
+        Expr::Call(call) => {
+            if let Expr::Path(expr_path) = &*call.func {
+                let func_name = expr_path
+                    .path
+                    .segments
+                    .last()
+                    .unwrap()
+                    .ident
+                    .to_string()
+                    .to_lowercase();
+                let swap_functions = vec![
+                    "transfer",
+                    "transfer_from",
+                    "swap",
+                    "deposit",
+                    "withdraw",
+                    "exchange",
+                    "buy",
+                    "sell",
+                    "send",
+                    "receive",
+                    "trade",
+                    "mint",
+                    "burn",
+                ];
+                swap_functions.contains(&func_name.as_str())
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn is_expected_amount_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Path(expr_path) => {
+            let ident = expr_path
+                .path
+                .segments
+                .last()
+                .unwrap()
+                .ident
+                .to_string()
+                .to_lowercase();
+            ident.contains("expected")
+                || ident.contains("min_amount")
+                || ident.contains("min_out")
+                || ident.contains("minimum")
+                || ident.contains("limit")
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn is_actual_amount_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Path(expr_path) => {
+            let ident = expr_path
+                .path
+                .segments
+                .last()
+                .unwrap()
+                .ident
+                .to_string()
+                .to_lowercase();
+            ident.contains("actual")
+                || ident.contains("amount_out")
+                || ident.contains("received")
+                || ident.contains("output")
+                || ident.contains("result")
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use syn::{Expr, FnArg, Pat};
+
+use super::access_control::{modifies_state, performs_access_control};
+use super::slippage::is_swap_method_call;
+use crate::evidence::{account_evidence, Property};
+use crate::syntax::{call_args, call_name, for_each_expr, strip_pat};
+use crate::walk::FnItem;
+
+// Sensitive operations a function performs itself or through the functions it calls
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Sink {
+    Swap,
+    StateWrite,
+}
+
+// What calling a crate function amounts to, seen from the call site
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    // properties verified on each parameter before the function returns, the receiver
+    // first for methods, e.g. `[[Owner], []]` for `fn assert_owned_by(acc, program_id)`
+    pub checks: Vec<Vec<Property>>,
+    // leaves with an error unless the signer or the stored authority checks out
+    pub access_control: bool,
+    pub sinks: BTreeSet<Sink>,
+}
+
+impl Summary {
+    // Several functions share a name: a call may reach any of them, so only what all of
+    // them check and everything any of them reaches holds at the call site
+    fn merge(&mut self, other: Summary) {
+        self.checks.truncate(other.checks.len());
+        for (checks, other) in self.checks.iter_mut().zip(other.checks) {
+            checks.retain(|property| other.contains(property));
+        }
+        self.access_control &= other.access_control;
+        self.sinks.extend(other.sinks);
+    }
+}
+
+// How a call names the functions it may reach: `foo(..)` and `Type::foo(..)` free and
+// associated functions, `x.foo(..)` methods taking `self`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Callee {
+    Function(String),
+    Method(String),
+}
+
+impl Callee {
+    fn of(func: &FnItem) -> Self {
+        if func.sig.receiver().is_some() {
+            Callee::Method(func.name())
+        } else {
+            Callee::Function(func.name())
+        }
+    }
+
+    // What `call` may reach, best match first. `Type::check(x, ..)` may also name the method
+    // `x.check(..)`, whose receiver then comes first either way
+    fn targets(call: &Expr) -> Vec<Callee> {
+        match call {
+            Expr::MethodCall(method_call) => vec![Callee::Method(method_call.method.to_string())],
+            Expr::Call(expr_call) => {
+                let Some(name) = call_name(call) else {
+                    return Vec::new();
+                };
+                let qualified = matches!(&*expr_call.func, Expr::Path(expr_path) if expr_path.path.segments.len() > 1);
+                if qualified {
+                    vec![Callee::Function(name.clone()), Callee::Method(name)]
+                } else {
+                    vec![Callee::Function(name)]
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+// Calls between the functions of the program, matched by name like the rest of the
+// analysis, with a summary per function name and kind of call
+#[derive(Default)]
+pub struct CallGraph {
+    callees: HashMap<String, BTreeSet<String>>,
+    summaries: HashMap<Callee, Summary>,
+}
+
+impl CallGraph {
+    pub fn new(functions: &[FnItem]) -> Self {
+        let mut graph = CallGraph::default();
+        // the functions behind each callee, and the functions calling each callee
+        let mut definitions: HashMap<Callee, Vec<&FnItem>> = HashMap::new();
+        let mut callers: HashMap<Callee, HashSet<Callee>> = HashMap::new();
+        for func in functions {
+            let callee = Callee::of(func);
+            definitions.entry(callee.clone()).or_default().push(func);
+            let callees = graph.callees.entry(func.name()).or_default();
+            for_each_expr(func.block, |expr| {
+                if let Some(name) = call_name(expr) {
+                    callees.insert(name);
+                }
+                for target in Callee::targets(expr) {
+                    callers.entry(target).or_default().insert(callee.clone());
+                }
+            });
+        }
+
+        // a summary grows with the summaries of its callees, so once one changes its callers
+        // are summarized again, until nothing changes. each callee is summarized at most once
+        // more than there are callees, which bounds the work should summaries keep changing
+        let mut pending = VecDeque::new();
+        let mut queued = HashSet::new();
+        for func in functions {
+            if queued.insert(Callee::of(func)) {
+                pending.push_back(Callee::of(func));
+            }
+        }
+        let mut rounds: HashMap<Callee, usize> = HashMap::new();
+        while let Some(callee) = pending.pop_front() {
+            queued.remove(&callee);
+            let round = rounds.entry(callee.clone()).or_default();
+            if *round > definitions.len() {
+                continue;
+            }
+            *round += 1;
+
+            let summary = definitions[&callee]
+                .iter()
+                .map(|func| summarize(func, &graph))
+                .reduce(|mut merged, summary| {
+                    merged.merge(summary);
+                    merged
+                });
+            let Some(summary) = summary.filter(|summary| graph.summaries.get(&callee) != Some(summary)) else {
+                continue;
+            };
+            graph.summaries.insert(callee.clone(), summary);
+            for caller in callers.get(&callee).into_iter().flatten() {
+                if queued.insert(caller.clone()) {
+                    pending.push_back(caller.clone());
+                }
+            }
+        }
+        graph
+    }
+
+    // The summary of the crate function `call` calls, if it calls one
+    pub fn summary(&self, call: &Expr) -> Option<&Summary> {
+        Callee::targets(call)
+            .iter()
+            .find_map(|callee| self.summaries.get(callee))
+    }
+
+    // Arguments of `call` the called helper verifies `property` on, e.g. `vault` for
+    // `assert_owned_by(vault, program_id)?`
+    pub fn verified_args<'e>(&self, call: &'e Expr, property: Property) -> Vec<&'e Expr> {
+        let Some(summary) = self.summary(call) else {
+            return Vec::new();
+        };
+        call_args(call)
+            .into_iter()
+            .zip(&summary.checks)
+            .filter(|(_, checks)| checks.contains(&property))
+            .map(|(arg, _)| arg)
+            .collect()
+    }
+
+    // `roots` and every function called from them, directly or not
+    pub fn reachable_from(&self, roots: HashSet<String>) -> HashSet<String> {
+        let mut reachable = roots;
+        let mut pending: Vec<String> = reachable.iter().cloned().collect();
+        while let Some(name) = pending.pop() {
+            for callee in self.callees.get(&name).into_iter().flatten() {
+                if reachable.insert(callee.clone()) {
+                    pending.push(callee.clone());
+                }
+            }
+        }
+        reachable
+    }
+
+    pub fn reaches(&self, from: &str, to: &str) -> bool {
+        self.reachable_from(HashSet::from([from.to_string()])).contains(to)
+    }
+}
+
+fn summarize(func: &FnItem, graph: &CallGraph) -> Summary {
+    let evidence = account_evidence(func, graph);
+    let checks = func
+        .sig
+        .inputs
+        .iter()
+        .map(|input| {
+            let name = match input {
+                FnArg::Receiver(_) => "self".to_string(),
+                FnArg::Typed(pat_type) => match strip_pat(&pat_type.pat) {
+                    Pat::Ident(pat_ident) => pat_ident.ident.to_string(),
+                    _ => return Vec::new(),
+                },
+            };
+            [Property::Signer, Property::Writable, Property::Owner]
+                .into_iter()
+                .filter(|property| evidence.verified_on_return(&name, *property, func.block))
+                .collect()
+        })
+        .collect();
+
+    let mut summary = Summary {
+        checks,
+        access_control: performs_access_control(func),
+        sinks: BTreeSet::new(),
+    };
+    if modifies_state(func) {
+        summary.sinks.insert(Sink::StateWrite);
+    }
+    for_each_expr(func.block, |expr| {
+        if is_swap_method_call(expr) {
+            summary.sinks.insert(Sink::Swap);
+        }
+        if let Some(callee) = graph.summary(expr) {
+            summary.access_control |= callee.access_control;
+            summary.sinks.extend(callee.sinks.iter().copied());
+        }
+    });
+    summary
+}
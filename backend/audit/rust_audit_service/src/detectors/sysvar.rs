@@ -0,0 +1,137 @@
+use syn::spanned::Spanned;
+use syn::Expr;
+
+use super::access_control::account_data_borrow_owner;
+use super::arbitrary_cpi::pins_program_id;
+use super::{AnalysisContext, Detector};
+use crate::cfg::Cfg;
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{call_args, call_name, contains_expr, for_each_expr, root_ident};
+use crate::walk::FnItem;
+
+const ID: &str = "sysvar-spoofing";
+const SEVERITY: Severity = Severity::High;
+
+// sysvar types and the module their id lives in
+const SYSVARS: [(&str, &str); 8] = [
+    ("Rent", "rent"),
+    ("Clock", "clock"),
+    ("Instructions", "instructions"),
+    ("SlotHashes", "slot_hashes"),
+    ("EpochSchedule", "epoch_schedule"),
+    ("StakeHistory", "stake_history"),
+    ("Fees", "fees"),
+    ("RecentBlockhashes", "recent_blockhashes"),
+];
+
+pub struct SysvarSpoofingDetector;
+
+impl Detector for SysvarSpoofingDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Sysvar read from a caller-supplied account whose key is never checked against the sysvar id, instead of `Sysvar::get()`."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions.iter().flat_map(check_sysvar_spoofing).collect()
+    }
+}
+
+fn check_sysvar_spoofing(func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let cfg = Cfg::new(func.block);
+
+    for_each_expr(func.block, |expr| {
+        let Some((sysvar, module, binding, confidence)) = sysvar_read(expr) else {
+            return;
+        };
+        // `sysvar::rent::ID`, `rent::check_id(..)` or `Rent::id()`, not the id of another sysvar
+        let has_id_check = cfg.facts_of(expr).into_iter().any(|(fact, holds)| {
+            pins_program_id(fact, holds, &binding, |name| name == module || name == sysvar)
+        });
+        if has_id_check {
+            return;
+        }
+
+        issues.push(
+            func.finding(
+                ID,
+                SEVERITY,
+                expr.span(),
+                format!(
+                    "{} '{}' reads the {} sysvar from account '{}' without checking its key against the sysvar id, so a fake account can be passed in.",
+                    func.kind_label(),
+                    func.name(),
+                    sysvar,
+                    binding
+                ),
+            )
+            .with_confidence(confidence)
+            .with_remediation(&if module == "instructions" {
+                format!(
+                    "Use `load_instruction_at_checked`/`load_current_index_checked`, or return an error unless `sysvar::instructions::check_id({}.key)`.",
+                    binding
+                )
+            } else {
+                format!(
+                    "Use `{}::get()`, or return an error unless `{}.key == &sysvar::{}::ID`.",
+                    sysvar, binding, module
+                )
+            }),
+        );
+    });
+
+    issues
+}
+
+// The sysvar, its id module, the account it is read from and how likely the read is
+// unchecked. `load_instruction_at` and `load_current_index` never look at the key;
+// recent `from_account_info` implementations do, older ones and hand-rolled reads do not
+fn sysvar_read(expr: &Expr) -> Option<(&'static str, &'static str, String, Confidence)> {
+    let Expr::Call(call) = expr else {
+        return None;
+    };
+    let Expr::Path(expr_path) = &*call.func else {
+        return None;
+    };
+    let segments: Vec<String> = expr_path.path.segments.iter().map(|segment| segment.ident.to_string()).collect();
+    let args = call_args(expr);
+
+    match segments.last()?.as_str() {
+        "load_instruction_at" | "load_current_index" => {
+            let binding = args.iter().find_map(|arg| data_owner(arg))?;
+            Some(("Instructions", "instructions", binding, Confidence::High))
+        }
+        "from_account_info" | "deserialize" if segments.len() >= 2 => {
+            let (sysvar, module) = SYSVARS.iter().find(|(sysvar, _)| segments[segments.len() - 2] == *sysvar)?;
+            let binding = match segments.last()?.as_str() {
+                "from_account_info" => root_ident(args.first()?)?,
+                _ => args.iter().find_map(|arg| data_owner(arg))?,
+            };
+            let confidence = if call_name(expr).as_deref() == Some("deserialize") {
+                Confidence::High
+            } else {
+                Confidence::Low
+            };
+            Some((sysvar, module, binding, confidence))
+        }
+        _ => None,
+    }
+}
+
+// `vault` for an argument that borrows `vault.data`
+fn data_owner(arg: &Expr) -> Option<String> {
+    let mut owner = None;
+    contains_expr(arg, |inner| {
+        owner = account_data_borrow_owner(inner);
+        owner.is_some()
+    });
+    owner
+}
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::{BinOp, Expr, FnArg, Pat};
+
+use super::account_close::lamports_borrow_owner;
+use super::panic::program_roots;
+use super::pda::is_instruction_param;
+use super::{AnalysisContext, Detector};
+use crate::cfg::Cfg;
+use crate::finding::{Confidence, Finding, Severity, TraceStep};
+use crate::syntax::{
+    call_args, call_name, contains_expr, for_each_expr, member_name, mentions_ident, render, strip, strip_pat,
+};
+use crate::taint::{Taint, Trace};
+use crate::walk::FnItem;
+
+// constructors of `Instruction` that take the program id first
+const INSTRUCTION_CONSTRUCTORS: [&str; 3] = ["new_with_bytes", "new_with_borsh", "new_with_bincode"];
+
+// Where attacker controlled data must not end up without a check
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaintSink {
+    Lamports,
+    ProgramId,
+    PdaSeeds,
+    Index,
+    LoopBound,
+}
+
+// One rule of the `taint-*` family per sink. they share the propagation of instruction
+// data from the program roots into helpers, and report the path it took as a trace
+pub struct TaintDetector {
+    sink: TaintSink,
+}
+
+impl TaintDetector {
+    pub fn new(sink: TaintSink) -> Self {
+        TaintDetector { sink }
+    }
+}
+
+impl Detector for TaintDetector {
+    fn id(&self) -> &'static str {
+        match self.sink {
+            TaintSink::Lamports => "taint-lamports",
+            TaintSink::ProgramId => "taint-invoke-program",
+            TaintSink::PdaSeeds => "taint-pda-seeds",
+            TaintSink::Index => "taint-index",
+            TaintSink::LoopBound => "taint-loop-bound",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self.sink {
+            TaintSink::Lamports => "Lamports debited by an amount taken from instruction data that is never compared against a balance or limit.",
+            TaintSink::ProgramId => "Program id of an invoked instruction taken from instruction data without comparing it to the expected program.",
+            TaintSink::PdaSeeds => "PDA seeds or bump taken from instruction data without a check.",
+            TaintSink::Index => "Slice or array indexed with a value taken from instruction data without a bounds check.",
+            TaintSink::LoopBound => "Loop bounded by a value taken from instruction data without an upper limit.",
+        }
+    }
+
+    fn default_severity(&self) -> Severity {
+        match self.sink {
+            TaintSink::ProgramId => Severity::Critical,
+            TaintSink::Lamports | TaintSink::PdaSeeds => Severity::High,
+            TaintSink::Index | TaintSink::LoopBound => Severity::Medium,
+        }
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions
+            .iter()
+            .zip(&ctx.taint)
+            .filter_map(|(func, taint)| Some(self.check_sinks(func, taint.as_ref()?)))
+            .flatten()
+            .collect()
+    }
+}
+
+impl TaintDetector {
+    fn check_sinks(&self, func: &FnItem, taint: &Taint) -> Vec<Finding> {
+        let mut issues = Vec::new();
+        let cfg = Cfg::new(func.block);
+
+        for_each_expr(func.block, |expr| {
+            for (value, what) in sink_values(self.sink, expr) {
+                let Some(mut trace) = taint.trace_of(value) else {
+                    continue;
+                };
+                // a comparison on every path to the sink bounds or checks the value
+                let names = taint.tainted_names(value);
+                if cfg.guards_of(value).into_iter().any(|cond| compares_any(cond, &names)) {
+                    continue;
+                }
+
+                trace.push(TraceStep {
+                    location: func.location(value.span()),
+                    note: format!("{} `{}`", what, render(value)),
+                });
+                issues.push(
+                    func.finding(
+                        self.id(),
+                        self.default_severity(),
+                        value.span(),
+                        format!(
+                            "{} '{}' {} `{}`, which comes from instruction data and is never checked.",
+                            func.kind_label(),
+                            func.name(),
+                            what,
+                            render(value)
+                        ),
+                    )
+                    .with_confidence(Confidence::Medium)
+                    .with_remediation(self.remediation())
+                    .with_trace(trace),
+                );
+            }
+        });
+
+        issues
+    }
+
+    fn remediation(&self) -> &'static str {
+        match self.sink {
+            TaintSink::Lamports => "Compare the amount against the balance and any limits of the account and return an error before moving lamports.",
+            TaintSink::ProgramId => "Compare the program id against the expected program, e.g. `spl_token::ID`, before building and invoking the instruction.",
+            TaintSink::PdaSeeds => "Derive seeds from account keys and constants, and use the canonical bump from `find_program_address` or the one stored in the account.",
+            TaintSink::Index => "Use `.get(..)` and return an error when the index is out of range, or compare it with the length first.",
+            TaintSink::LoopBound => "Return an error when the bound exceeds a fixed maximum before the loop.",
+        }
+    }
+}
+
+// The values an expression hands to `sink`, with what it does with them
+fn sink_values(sink: TaintSink, expr: &Expr) -> Vec<(&Expr, String)> {
+    match (sink, expr) {
+        // `**vault.lamports.borrow_mut() -= amount`. the runtime balances every credit against
+        // a debit, so only what leaves an account matters
+        (TaintSink::Lamports, Expr::Binary(expr_binary)) if matches!(expr_binary.op, BinOp::SubAssign(_)) => {
+            lamports_borrow_owner(strip(&expr_binary.left))
+                .map(|account| (&*expr_binary.right, format!("debits the lamports of '{}' by", account)))
+                .into_iter()
+                .collect()
+        }
+        (TaintSink::Lamports, Expr::Assign(expr_assign)) => {
+            let Some(account) = lamports_borrow_owner(strip(&expr_assign.left)) else {
+                return Vec::new();
+            };
+            // `checked_sub(..).ok_or(..)?` already fails on an overdraft
+            let debits = contains_expr(
+                &expr_assign.right,
+                |inner| matches!(inner, Expr::Binary(expr_binary) if matches!(expr_binary.op, BinOp::Sub(_))),
+            );
+            if !debits {
+                return Vec::new();
+            }
+            vec![(&*expr_assign.right, format!("debits the lamports of '{}' to", account))]
+        }
+        // `Instruction { program_id, .. }` or `Instruction::new_with_bytes(program_id, ..)`
+        (TaintSink::ProgramId, Expr::Struct(expr_struct))
+            if expr_struct
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Instruction") =>
+        {
+            expr_struct
+                .fields
+                .iter()
+                .filter(|field| member_name(&field.member) == "program_id")
+                .map(|field| (&field.expr, "builds an instruction for the program".to_string()))
+                .collect()
+        }
+        (TaintSink::ProgramId, Expr::Call(_))
+            if call_name(expr).is_some_and(|name| INSTRUCTION_CONSTRUCTORS.contains(&name.as_str())) =>
+        {
+            call_args(expr)
+                .first()
+                .map(|program_id| (*program_id, "builds an instruction for the program".to_string()))
+                .into_iter()
+                .collect()
+        }
+        // seeds of `create_program_address(seeds, program_id)` and the signer seeds of `invoke_signed`
+        (TaintSink::PdaSeeds, Expr::Call(_)) => {
+            let args = call_args(expr);
+            let seeds = match call_name(expr).as_deref() {
+                Some("create_program_address" | "find_program_address") => args.first(),
+                Some("invoke_signed") => args.last(),
+                _ => None,
+            };
+            seeds
+                .map(|seeds| (*seeds, "derives a program address from the seeds".to_string()))
+                .into_iter()
+                .collect()
+        }
+        (TaintSink::Index, Expr::Index(expr_index)) => {
+            vec![(
+                &*expr_index.index,
+                format!("indexes `{}` with", render(&expr_index.expr)),
+            )]
+        }
+        (TaintSink::LoopBound, Expr::ForLoop(for_loop)) => match strip(&for_loop.expr) {
+            Expr::Range(range) => range
+                .end
+                .iter()
+                .map(|end| (&**end, "runs a `for` loop up to".to_string()))
+                .collect(),
+            _ => Vec::new(),
+        },
+        (TaintSink::LoopBound, Expr::While(while_loop)) => match strip(&while_loop.cond) {
+            Expr::Binary(expr_binary)
+                if matches!(
+                    expr_binary.op,
+                    BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_) | BinOp::Ge(_)
+                ) =>
+            {
+                vec![(&*while_loop.cond, "runs a `while` loop on".to_string())]
+            }
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+// A comparison in `cond` on one of `names`, e.g. `amount > vault.balance` or
+// `program_id != &spl_token::ID`
+fn compares_any(cond: &Expr, names: &[String]) -> bool {
+    contains_expr(cond, |expr| match expr {
+        Expr::Binary(comparison)
+            if matches!(
+                comparison.op,
+                BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_) | BinOp::Ge(_) | BinOp::Eq(_) | BinOp::Ne(_)
+            ) =>
+        {
+            names.iter().any(|name| mentions_ident(expr, name))
+        }
+        _ => false,
+    })
+}
+
+// Instruction data enters through the parameters of the program roots and reaches helpers
+// through the arguments they are called with. every function gets the parameters that
+// carry it, with the path it took to get there, in the order of `ctx.functions`
+pub(crate) fn tainted_functions(ctx: &AnalysisContext) -> Vec<Option<Taint>> {
+    let mut seeds: HashMap<String, HashMap<String, Trace>> = HashMap::new();
+    let roots = program_roots(ctx);
+    for func in ctx.functions.iter().filter(|func| roots.contains(&func.name())) {
+        for (name, span) in params(func) {
+            if is_instruction_param(func, &name) {
+                let step = TraceStep {
+                    location: func.location(span),
+                    note: format!("'{}' is instruction data passed to '{}'", name, func.name()),
+                };
+                seeds.entry(func.name()).or_default().insert(name, vec![step]);
+            }
+        }
+    }
+
+    let mut pending: Vec<String> = seeds.keys().cloned().collect();
+    while let Some(name) = pending.pop() {
+        let caller_seeds = seeds[&name].clone();
+        for func in ctx.functions.iter().filter(|func| func.name() == name) {
+            let taint = Taint::new(func, &caller_seeds);
+            for_each_expr(func.block, |expr| {
+                let Some(callee) = call_name(expr) else {
+                    return;
+                };
+                for callee_func in ctx.functions.iter().filter(|func| func.name() == callee) {
+                    for (arg, (param, _)) in call_args(expr).into_iter().zip(params(callee_func)) {
+                        let callee_seeds = seeds.entry(callee.clone()).or_default();
+                        if param.is_empty() || callee_seeds.contains_key(&param) {
+                            continue;
+                        }
+                        let Some(mut trace) = taint.trace_of(arg) else {
+                            continue;
+                        };
+                        trace.push(TraceStep {
+                            location: func.location(arg.span()),
+                            note: format!("`{}` is passed to '{}' as '{}'", render(arg), callee, param),
+                        });
+                        callee_seeds.insert(param, trace);
+                        pending.push(callee.clone());
+                    }
+                }
+            });
+        }
+    }
+
+    ctx.functions
+        .iter()
+        .map(|func| {
+            let seeds = seeds.get(&func.name()).filter(|seeds| !seeds.is_empty())?;
+            Some(Taint::new(func, seeds))
+        })
+        .collect()
+}
+
+// Parameter names in order, the receiver as `self` and an empty name for patterns
+fn params(func: &FnItem) -> Vec<(String, Span)> {
+    func.sig
+        .inputs
+        .iter()
+        .map(|input| match input {
+            FnArg::Receiver(receiver) => ("self".to_string(), receiver.span()),
+            FnArg::Typed(pat_type) => match strip_pat(&pat_type.pat) {
+                Pat::Ident(pat_ident) => (pat_ident.ident.to_string(), pat_ident.span()),
+                pat => (String::new(), pat.span()),
+            },
+        })
+        .collect()
+}
@@ -0,0 +1,198 @@
+use std::ptr;
+
+use syn::spanned::Spanned;
+use syn::{Expr, Pat};
+
+use super::access_control::account_data_borrow_owner;
+use super::type_cosplay::deserialized_type;
+use super::{AnalysisContext, Detector};
+use crate::cfg::{equality, Cfg};
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{
+    call_args, call_name, contains_expr, for_each_expr, local_init, member_name, path_ident, root_ident,
+    starts_before, strip, strip_pat, visit_locals,
+};
+use crate::walk::FnItem;
+
+const ID: &str = "unvalidated-token-account";
+const SEVERITY: Severity = Severity::High;
+
+// types `spl_token::state::Account` is usually imported as
+const TOKEN_ACCOUNT_TYPES: [&str; 2] = ["Account", "TokenAccount"];
+
+const UNPACK_METHODS: [&str; 3] = ["unpack", "unpack_unchecked", "unpack_from_slice"];
+
+// token instructions that move or destroy the balance of the account passed in
+const TOKEN_SINKS: [&str; 4] = ["transfer", "transfer_checked", "burn", "burn_checked"];
+
+// fields of an unpacked token account that have to match what the handler expects
+const CHECKED_FIELDS: [&str; 2] = ["mint", "owner"];
+
+pub struct TokenAccountValidationDetector;
+
+impl Detector for TokenAccountValidationDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "SPL token account unpacked and then transferred from or burned without checking its `mint` and `owner` fields."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        ctx.functions.iter().flat_map(check_token_account_validation).collect()
+    }
+}
+
+// A token account read in a handler: the local holding the unpacked state and
+// the AccountInfo it was unpacked from
+struct UnpackedTokenAccount<'a> {
+    state: String,
+    account: String,
+    call: &'a Expr,
+}
+
+fn check_token_account_validation(func: &FnItem) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let cfg = Cfg::new(func.block);
+
+    for unpacked in unpacked_token_accounts(func) {
+        let Some(sink) = first_token_sink(func, &unpacked) else {
+            continue;
+        };
+        let facts = cfg.facts_of(sink);
+        let missing: Vec<&str> = CHECKED_FIELDS
+            .iter()
+            .copied()
+            .filter(|field| {
+                !facts
+                    .iter()
+                    .any(|(fact, holds)| pins_field(fact, *holds, &unpacked.state, field))
+            })
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+
+        let sink_name = call_name(sink).unwrap_or_default();
+        let (fields, confidence) = match missing.as_slice() {
+            [field] => (format!("its `{}`", field), Confidence::Medium),
+            _ => ("either its `mint` or its `owner`".to_string(), Confidence::High),
+        };
+        issues.push(
+            func.finding(
+                ID,
+                SEVERITY,
+                unpacked.call.span(),
+                format!(
+                    "{} '{}' unpacks token account '{}' and uses it in `{}` without checking {}, so a token account for another mint or owner can be passed in.",
+                    func.kind_label(),
+                    func.name(),
+                    unpacked.account,
+                    sink_name,
+                    fields
+                ),
+            )
+            .with_confidence(confidence)
+            .with_remediation(&format!(
+                "Return an error unless {} before calling `{}`.",
+                missing
+                    .iter()
+                    .map(|field| format!("`{}.{}` matches the expected {}", unpacked.state, field, field))
+                    .collect::<Vec<_>>()
+                    .join(" and "),
+                sink_name
+            )),
+        );
+    }
+
+    issues
+}
+
+// `let source = spl_token::state::Account::unpack(&source_info.data.borrow())?`
+fn unpacked_token_accounts<'a>(func: &FnItem<'a>) -> Vec<UnpackedTokenAccount<'a>> {
+    let mut calls = Vec::new();
+    for_each_expr(func.block, |expr| {
+        if is_token_account_unpack(func, expr) {
+            calls.push(expr);
+        }
+    });
+
+    let mut unpacked = Vec::new();
+    for call in calls {
+        let Some(account) = call_args(call).iter().find_map(|arg| unpacked_from(func, arg, call)) else {
+            continue;
+        };
+        let mut state = None;
+        visit_locals(func.block, |local| {
+            if let (Pat::Ident(pat_ident), Some(init)) = (strip_pat(&local.pat), &local.init) {
+                if contains_expr(&init.expr, |inner| ptr::eq(inner, call)) {
+                    state = Some(pat_ident.ident.to_string());
+                }
+            }
+        });
+        if let Some(state) = state {
+            unpacked.push(UnpackedTokenAccount { state, account, call });
+        }
+    }
+    unpacked
+}
+
+fn is_token_account_unpack(func: &FnItem, expr: &Expr) -> bool {
+    call_name(expr).is_some_and(|name| UNPACK_METHODS.contains(&name.as_str()))
+        && deserialized_type(func, expr).is_some_and(|ty| TOKEN_ACCOUNT_TYPES.contains(&ty.as_str()))
+}
+
+// The account whose data an unpack argument borrows, following a local such as
+// `let data = source_info.try_borrow_data()?`
+pub(crate) fn unpacked_from(func: &FnItem, arg: &Expr, call: &Expr) -> Option<String> {
+    let mut owner = None;
+    contains_expr(arg, |inner| {
+        owner = account_data_borrow_owner(inner);
+        owner.is_some()
+    });
+    if owner.is_some() {
+        return owner;
+    }
+    let name = path_ident(arg)?;
+    let init = local_init(func.block, &name, call)?;
+    unpacked_from(func, init, call)
+}
+
+// Whether `fact` evaluating to `holds` makes `state.field` equal to something else:
+// `vault.mint == expected_mint` holding or `vault.mint != expected_mint` failing
+fn pins_field(fact: &Expr, holds: bool, state: &str, field: &str) -> bool {
+    let is_field = |expr: &Expr| match strip(expr) {
+        Expr::Field(expr_field) => {
+            member_name(&expr_field.member) == field && root_ident(&expr_field.base).as_deref() == Some(state)
+        }
+        _ => false,
+    };
+    match equality(fact, holds) {
+        Some((left, right, true)) => is_field(left) != is_field(right),
+        _ => false,
+    }
+}
+
+// The first transfer or burn after the unpack that takes the token account
+fn first_token_sink<'a>(func: &FnItem<'a>, unpacked: &UnpackedTokenAccount) -> Option<&'a Expr> {
+    let mut sink = None;
+    for_each_expr(func.block, |expr| {
+        if sink.is_some() || !starts_before(unpacked.call, expr) {
+            return;
+        }
+        let is_sink = call_name(expr).is_some_and(|name| TOKEN_SINKS.contains(&name.as_str()));
+        if is_sink
+            && call_args(expr)
+                .iter()
+                .any(|arg| root_ident(arg).as_deref() == Some(unpacked.account.as_str()))
+        {
+            sink = Some(expr);
+        }
+    });
+    sink
+}
@@ -0,0 +1,317 @@
+use std::collections::HashSet;
+use std::ptr;
+
+use syn::spanned::Spanned;
+use syn::{Expr, Fields, GenericArgument, Item, Lit, Pat, Type};
+
+use super::access_control::account_data_borrow_owner;
+use super::ownership::is_deserialization_call;
+use super::token_account::unpacked_from;
+use super::{AnalysisContext, Detector};
+use crate::cfg::Cfg;
+use crate::finding::{Confidence, Finding, Severity};
+use crate::syntax::{
+    call_args, call_name, contains_expr, derives, encloses, for_each_expr, local_init, member_name, path_ident, starts_before,
+    strip_pat, visit_locals,
+};
+use crate::walk::{self, type_name, FnItem};
+
+const ID: &str = "type-cosplay";
+const SEVERITY: Severity = Severity::High;
+
+// fields that tell one account type apart from another
+const TAG_FIELDS: [&str; 5] = ["discriminator", "account_type", "tag", "kind", "account_key"];
+
+// readers that stop once the struct is filled instead of rejecting trailing bytes
+const PREFIX_READERS: [&str; 2] = ["deserialize", "unpack_unchecked"];
+
+// path segments in front of `try_from_slice` that name a trait rather than the target type
+const DESERIALIZE_TRAITS: [&str; 4] = ["BorshDeserialize", "AnchorDeserialize", "Pack", "Self"];
+
+pub struct TypeCosplayDetector;
+
+impl Detector for TypeCosplayDetector {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Account data deserialized into a struct that shares its layout with another account struct, with no discriminator check."
+    }
+
+    fn default_severity(&self) -> Severity {
+        SEVERITY
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let accounts = account_structs(ctx);
+        if accounts.len() < 2 {
+            return Vec::new();
+        }
+        ctx.functions
+            .iter()
+            .flat_map(|func| check_type_cosplay(func, &accounts))
+            .collect()
+    }
+}
+
+// A Borsh or `Pack` account struct and its serialized layout
+struct AccountLayout {
+    name: String,
+    layout: Vec<LayoutPart>,
+}
+
+// fixed size fields only differ by their size, so `Pubkey` and `[u8; 32]` line up
+#[derive(PartialEq)]
+enum LayoutPart {
+    Bytes(usize),
+    Other(String),
+}
+
+fn check_type_cosplay(func: &FnItem, accounts: &[AccountLayout]) -> Vec<Finding> {
+    let mut issues = Vec::new();
+    let cfg = Cfg::new(func.block);
+
+    for_each_expr(func.block, |expr| {
+        if !matches!(expr, Expr::Call(_) | Expr::MethodCall(_)) || !is_deserialization_call(expr) {
+            return;
+        }
+        if !reads_account_data(func, expr) || is_tag_checked(func, &cfg, expr) {
+            return;
+        }
+        let Some(type_name) = deserialized_type(func, expr) else {
+            return;
+        };
+        let Some(target) = accounts.iter().find(|account| account.name == type_name) else {
+            return;
+        };
+        let reads_prefix = call_name(expr).is_some_and(|name| PREFIX_READERS.contains(&name.as_str()));
+        let lookalikes: Vec<&str> = accounts
+            .iter()
+            .filter(|other| {
+                other.name != target.name && layouts_compatible(&target.layout, &other.layout, reads_prefix)
+            })
+            .map(|other| other.name.as_str())
+            .collect();
+        if lookalikes.is_empty() {
+            return;
+        }
+
+        issues.push(
+            func.finding(
+                ID,
+                SEVERITY,
+                expr.span(),
+                format!(
+                    "{} '{}' deserializes account data as '{}' without checking a discriminator, but {} {} its layout and could be passed in its place.",
+                    func.kind_label(),
+                    func.name(),
+                    target.name,
+                    lookalikes.iter().map(|name| format!("'{}'", name)).collect::<Vec<_>>().join(", "),
+                    if lookalikes.len() == 1 { "shares" } else { "share" }
+                ),
+            )
+            .with_confidence(Confidence::Medium)
+            .with_remediation(
+                "Store a distinct discriminator or `account_type` in every account struct and return an error when it does not match before using the data.",
+            ),
+        );
+    });
+
+    issues
+}
+
+// Structs deriving a Borsh/serde `Deserialize` or implementing `Pack`. Anchor `#[account]`
+// structs are left out, Anchor prefixes them with its own discriminator
+fn account_structs(ctx: &AnalysisContext) -> Vec<AccountLayout> {
+    let items = walk::module_items(ctx.program);
+
+    let packed: HashSet<String> = items
+        .iter()
+        .filter_map(|module_item| match module_item.item {
+            Item::Impl(item_impl) => {
+                let (_, trait_path, _) = item_impl.trait_.as_ref()?;
+                let is_pack = trait_path.segments.last().is_some_and(|segment| segment.ident == "Pack");
+                is_pack.then(|| type_name(&item_impl.self_ty)).flatten()
+            }
+            _ => None,
+        })
+        .collect();
+
+    items
+        .iter()
+        .filter_map(|module_item| match module_item.item {
+            Item::Struct(item_struct) => {
+                let name = item_struct.ident.to_string();
+                let deserializable = derives(&item_struct.attrs).iter().any(|derive| derive.ends_with("Deserialize"));
+                let anchor_account = item_struct.attrs.iter().any(|attr| attr.path().is_ident("account"));
+                if !(deserializable || packed.contains(&name)) || anchor_account {
+                    return None;
+                }
+                let Fields::Named(fields) = &item_struct.fields else {
+                    return None;
+                };
+                Some(AccountLayout {
+                    name,
+                    layout: layout(fields.named.iter().map(|field| &field.ty)),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn layout<'a>(types: impl Iterator<Item = &'a Type>) -> Vec<LayoutPart> {
+    types
+        .map(|ty| match fixed_size(ty) {
+            Some(size) => LayoutPart::Bytes(size),
+            None => LayoutPart::Other(type_name(ty).unwrap_or_default()),
+        })
+        .collect()
+}
+
+// Serialized size of plain integer, bool, `Pubkey` and fixed array fields
+fn fixed_size(ty: &Type) -> Option<usize> {
+    match ty {
+        Type::Path(type_path) => match type_path.path.segments.last()?.ident.to_string().as_str() {
+            "bool" | "u8" | "i8" => Some(1),
+            "u16" | "i16" => Some(2),
+            "u32" | "i32" | "f32" => Some(4),
+            "u64" | "i64" | "f64" => Some(8),
+            "u128" | "i128" => Some(16),
+            "Pubkey" => Some(32),
+            _ => None,
+        },
+        Type::Array(type_array) => match &type_array.len {
+            Expr::Lit(expr_lit) => match &expr_lit.lit {
+                Lit::Int(len) => Some(len.base10_parse::<usize>().ok()? * fixed_size(&type_array.elem)?),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Bytes written for `other` decode as `target`: the same field layout or, for a reader that
+// accepts a prefix, `other` is `target` with fields appended. `try_from_slice` and `unpack`
+// reject the trailing bytes of a longer struct
+fn layouts_compatible(target: &[LayoutPart], other: &[LayoutPart], reads_prefix: bool) -> bool {
+    let fits = if reads_prefix { target.len() <= other.len() } else { target.len() == other.len() };
+    !target.is_empty() && fits && target.iter().zip(other).all(|(x, y)| x == y)
+}
+
+// Every use of the state the call deserializes runs after a guard on its tag, e.g.
+// `if state.discriminator != VAULT_DISCRIMINATOR { return Err(..) }`, or the call itself
+// runs after a guard on the first bytes of the account's raw data
+fn is_tag_checked(func: &FnItem, cfg: &Cfg, call: &Expr) -> bool {
+    let account = call_args(call).iter().find_map(|arg| unpacked_from(func, arg, call));
+    let state = deserialized_binding(func, call);
+    let guarded = |at: &Expr| {
+        cfg.guards_of(at).iter().any(|cond| {
+            contains_expr(cond, |inner| is_tag_read(func, inner, state.as_deref(), account.as_deref(), at))
+        })
+    };
+    if guarded(call) {
+        return true;
+    }
+    let Some(state) = state.as_deref() else {
+        return false;
+    };
+
+    let mut tag_reads = Vec::new();
+    let mut uses = Vec::new();
+    for_each_expr(func.block, |expr| {
+        if is_tag_read(func, expr, Some(state), None, expr) {
+            tag_reads.push(expr);
+        } else if path_ident(expr).as_deref() == Some(state) && starts_before(call, expr) {
+            uses.push(expr);
+        }
+    });
+    let uses: Vec<&Expr> = uses
+        .into_iter()
+        .filter(|at| !tag_reads.iter().any(|read| encloses(*read, *at)))
+        .collect();
+    // a state only read for its tag is checked as long as the tag is read at all
+    if uses.is_empty() {
+        return !tag_reads.is_empty();
+    }
+    uses.into_iter().all(guarded)
+}
+
+// `state.discriminator` or `state.account_type` of the deserialized state, or `data[0]` of
+// the account's raw data
+fn is_tag_read(func: &FnItem, expr: &Expr, state: Option<&str>, account: Option<&str>, at: &Expr) -> bool {
+    match expr {
+        Expr::Field(expr_field) => {
+            TAG_FIELDS.contains(&member_name(&expr_field.member).as_str())
+                && state.is_some_and(|state| path_ident(&expr_field.base).as_deref() == Some(state))
+        }
+        Expr::Index(expr_index) => {
+            account.is_some_and(|account| unpacked_from(func, &expr_index.expr, at).as_deref() == Some(account))
+        }
+        _ => false,
+    }
+}
+
+// `state` for `let state = Vault::try_from_slice(..)?`
+fn deserialized_binding(func: &FnItem, call: &Expr) -> Option<String> {
+    let mut binding = None;
+    visit_locals(func.block, |local| {
+        if let (Pat::Ident(pat_ident), Some(init)) = (strip_pat(&local.pat), &local.init) {
+            if contains_expr(&init.expr, |inner| ptr::eq(inner, call)) {
+                binding = Some(pat_ident.ident.to_string());
+            }
+        }
+    });
+    binding
+}
+
+// The call reads `acc.data.borrow()` directly or through a local such as `let data = acc.data.borrow();`
+fn reads_account_data(func: &FnItem, call: &Expr) -> bool {
+    call_args(call).iter().any(|arg| {
+        contains_expr(arg, |inner| account_data_borrow_owner(inner).is_some())
+            || path_ident(arg)
+                .and_then(|name| local_init(func.block, &name, call))
+                .is_some_and(|init| contains_expr(init, |inner| account_data_borrow_owner(inner).is_some()))
+    })
+}
+
+// `Vault` for `Vault::try_from_slice(..)`, `try_from_slice::<Vault>(..)` or
+// `let vault: Vault = BorshDeserialize::deserialize(..)`
+pub(crate) fn deserialized_type(func: &FnItem, call: &Expr) -> Option<String> {
+    match call {
+        Expr::Call(expr_call) => {
+            if let Expr::Path(expr_path) = &*expr_call.func {
+                let segments: Vec<_> = expr_path.path.segments.iter().collect();
+                if let [.., ty, _] = segments.as_slice() {
+                    let name = ty.ident.to_string();
+                    if name == "Self" {
+                        return func.owner.clone();
+                    }
+                    if !DESERIALIZE_TRAITS.contains(&name.as_str()) {
+                        return Some(name);
+                    }
+                }
+            }
+        }
+        Expr::MethodCall(method_call) => {
+            let turbofish = method_call.turbofish.as_ref().and_then(|turbofish| turbofish.args.first());
+            if let Some(GenericArgument::Type(ty)) = turbofish {
+                return type_name(ty);
+            }
+        }
+        _ => {}
+    }
+
+    // fall back to the annotation of the `let` the call initializes
+    let mut annotated = None;
+    visit_locals(func.block, |local| {
+        if let (Pat::Type(pat_type), Some(init)) = (&local.pat, &local.init) {
+            if contains_expr(&init.expr, |inner| ptr::eq(inner, call)) {
+                annotated = type_name(&pat_type.ty);
+            }
+        }
+    });
+    annotated
+}
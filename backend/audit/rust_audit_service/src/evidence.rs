@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use syn::spanned::Spanned;
+use syn::{BinOp, Block, Expr};
+
+use crate::cfg::{for_each_fact, Cfg};
+use crate::dataflow::Dataflow;
+use crate::detectors::CallGraph;
+use crate::syntax::{call_args, call_name, encloses, for_each_expr, member_name, root_ident, strip};
+use crate::walk::FnItem;
+
+// What a function can establish about an account before using it. a signer or
+// writable check says nothing about who owns the account, so each is kept apart
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Property {
+    Signer,
+    Writable,
+    Owner,
+}
+
+impl Property {
+    pub fn label(self) -> &'static str {
+        match self {
+            Property::Signer => "signed the transaction",
+            Property::Writable => "is writable",
+            Property::Owner => "is owned by the program",
+        }
+    }
+}
+
+// Where a check holds: in the code a branch node dominates, or after a helper call
+#[derive(Clone, Copy)]
+enum Check<'a> {
+    Branch { cond: &'a Expr, start: usize },
+    Call(&'a Expr),
+}
+
+// The checks of a function, per account rather than per name: a check on `payer` also
+// covers `let authority = payer`, and not the account bound to `payer` after a reassignment
+pub struct Evidence<'a> {
+    flow: Dataflow,
+    cfg: Cfg<'a>,
+    accounts: HashMap<String, Vec<(Property, Check<'a>)>>,
+}
+
+impl<'a> Evidence<'a> {
+    // True when `property` is checked on the account `binding` holds at `at` on every path
+    // to `at`: `at` runs in the branch a condition lets through, or after a helper call.
+    // a use within the condition itself, as in `key != state.authority || !authority.is_signer`,
+    // only matters once the whole condition has passed
+    pub fn verified(&self, binding: &str, property: Property, at: &impl Spanned) -> bool {
+        let Some(node) = self.cfg.node_of(at) else {
+            return false;
+        };
+        self.checks(binding, property, at).any(|check| match check {
+            Check::Branch { cond, start } => self.cfg.dominates(start, node) || encloses(cond, at),
+            Check::Call(call) => self.cfg.runs_before(call, at),
+        })
+    }
+
+    // True when `property` is checked on the account the parameter `binding` holds on every
+    // path on which the function returns normally, so a caller can count on it
+    pub fn verified_on_return(&self, binding: &str, property: Property, body: &Block) -> bool {
+        self.checks(binding, property, body).any(|check| match check {
+            Check::Branch { start, .. } => self.cfg.dominates_returns(start),
+            Check::Call(call) => self.cfg.node_of(call).is_some_and(|node| self.cfg.dominates_returns(node)),
+        })
+    }
+
+    fn checks(&self, binding: &str, property: Property, at: &impl Spanned) -> impl Iterator<Item = Check<'a>> + '_ {
+        self.accounts
+            .get(&self.flow.account_key(binding, at))
+            .into_iter()
+            .flatten()
+            .filter(move |(checked, _)| *checked == property)
+            .map(|(_, check)| *check)
+    }
+}
+
+// Signer, writable and owner checks per account. a check is a branch that only runs once
+// `x.is_signer`, `x.is_writable` or `x.owner == id` holds, such as the code after
+// `if !x.is_signer { return Err(..) }` or inside `if x.is_signer { .. }`, or a helper call
+// such as `assert_signer(x)?` or `assert_owned_by(x, program_id)?`
+pub fn account_evidence<'a>(func: &FnItem<'a>, call_graph: &CallGraph) -> Evidence<'a> {
+    let flow = Dataflow::new(func);
+    let cfg = Cfg::new(func.block);
+    let mut accounts: HashMap<String, Vec<(Property, Check<'a>)>> = HashMap::new();
+
+    for (cond, holds, start) in cfg.branches() {
+        for_each_fact(cond, holds, &mut |expr, holds| {
+            if let Some((binding, property)) = checked_property(expr, holds) {
+                let account = flow.account_key(&binding, expr);
+                accounts.entry(account).or_default().push((property, Check::Branch { cond, start }));
+            }
+        });
+    }
+
+    for_each_expr(func.block, |expr| {
+        // helpers of the program by what their summary says they verify, others by their name
+        let verified: Vec<(Property, &Expr)> = if call_graph.summary(expr).is_some() {
+            [Property::Signer, Property::Writable, Property::Owner]
+                .into_iter()
+                .flat_map(|property| {
+                    call_graph
+                        .verified_args(expr, property)
+                        .into_iter()
+                        .map(move |arg| (property, arg))
+                })
+                .collect()
+        } else {
+            match call_name(expr).and_then(|name| helper_property(&name)) {
+                Some(property) => call_args(expr).into_iter().map(|arg| (property, arg)).collect(),
+                None => Vec::new(),
+            }
+        };
+        for (property, arg) in verified {
+            if let Some(binding) = root_ident(arg) {
+                let account = flow.account_key(&binding, arg);
+                accounts.entry(account).or_default().push((property, Check::Call(expr)));
+            }
+        }
+    });
+
+    Evidence { flow, cfg, accounts }
+}
+
+// What `expr` evaluating to `holds` establishes: `x.is_signer` or `x.is_writable` holding,
+// `x.owner == program_id` holding or `x.owner != program_id` failing, and the same for
+// `x.owner.eq(&crate::ID)` and `x.owner.ne(&crate::ID)`
+fn checked_property(expr: &Expr, holds: bool) -> Option<(String, Property)> {
+    match strip(expr) {
+        Expr::Field(expr_field) if holds => match member_name(&expr_field.member).as_str() {
+            "is_signer" => Some((root_ident(&expr_field.base)?, Property::Signer)),
+            "is_writable" => Some((root_ident(&expr_field.base)?, Property::Writable)),
+            _ => None,
+        },
+        Expr::Binary(expr_binary) => {
+            let equal = match expr_binary.op {
+                BinOp::Eq(_) => holds,
+                BinOp::Ne(_) => !holds,
+                _ => return None,
+            };
+            let binding = owner_of(&expr_binary.left).or_else(|| owner_of(&expr_binary.right))?;
+            equal.then_some((binding, Property::Owner))
+        }
+        Expr::MethodCall(method_call) => {
+            let equal = match method_call.method.to_string().as_str() {
+                "eq" => holds,
+                "ne" => !holds,
+                _ => return None,
+            };
+            let binding = owner_of(&method_call.receiver)?;
+            equal.then_some((binding, Property::Owner))
+        }
+        _ => None,
+    }
+}
+
+// `vault` for `vault.owner`, `*vault.owner` or `vault.owner()`
+fn owner_of(expr: &Expr) -> Option<String> {
+    match strip(expr) {
+        Expr::Field(expr_field) if member_name(&expr_field.member) == "owner" => root_ident(&expr_field.base),
+        Expr::MethodCall(method_call) if method_call.method == "owner" => root_ident(&method_call.receiver),
+        _ => None,
+    }
+}
+
+// Validation helpers named after what they check, e.g. `assert_signer` or `check_account_owner`
+fn helper_property(name: &str) -> Option<Property> {
+    let name = name.to_lowercase();
+    if !["assert", "check", "verify", "require", "validate"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        return None;
+    }
+    if name.contains("signer") {
+        Some(Property::Signer)
+    } else if name.contains("writable") {
+        Some(Property::Writable)
+    } else if name.contains("owner") || name.contains("owned") {
+        Some(Property::Owner)
+    } else {
+        None
+    }
+}
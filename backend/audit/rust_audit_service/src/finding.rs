@@ -0,0 +1,176 @@
+use proc_macro2::Span;
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+// Where a finding points in the source. lines are 1-based like rustc,
+// columns are 1-based too so they can be pasted straight into an editor
+#[derive(Serialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    pub file: String,
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl Location {
+    pub fn from_span(file: &str, span: Span) -> Self {
+        let start = span.start();
+        let end = span.end();
+        Location {
+            file: file.to_string(),
+            start_line: start.line,
+            start_column: start.column + 1,
+            end_line: end.line,
+            end_column: end.column + 1,
+        }
+    }
+}
+
+// One step on the way from where a value enters the program to where a finding uses it
+#[derive(Serialize, Clone, Debug)]
+pub struct TraceStep {
+    pub location: Location,
+    pub note: String,
+}
+
+// One structured issue reported by a detector
+#[derive(Serialize, Clone, Debug)]
+pub struct Finding {
+    pub rule_id: String,
+    pub severity: Severity,
+    pub confidence: Confidence,
+    pub function_name: String,
+    // `::` separated module the function lives in, empty at the crate root
+    pub module_path: String,
+    pub message: String,
+    pub location: Location,
+    pub remediation: String,
+    // source to sink path for rules that follow data, empty for the others
+    pub trace: Vec<TraceStep>,
+}
+
+impl Finding {
+    pub fn new(rule_id: &str, severity: Severity, location: Location, message: String) -> Self {
+        Finding {
+            rule_id: rule_id.to_string(),
+            severity,
+            confidence: Confidence::Medium,
+            function_name: String::new(),
+            module_path: String::new(),
+            message,
+            location,
+            remediation: String::new(),
+            trace: Vec::new(),
+        }
+    }
+
+    pub fn with_confidence(mut self, confidence: Confidence) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    pub fn with_function(mut self, function_name: &str) -> Self {
+        self.function_name = function_name.to_string();
+        self
+    }
+
+    pub fn with_module_path(mut self, module_path: &[String]) -> Self {
+        self.module_path = module_path.join("::");
+        self
+    }
+
+    pub fn with_remediation(mut self, remediation: &str) -> Self {
+        self.remediation = remediation.to_string();
+        self
+    }
+
+    pub fn with_trace(mut self, trace: Vec<TraceStep>) -> Self {
+        self.trace = trace;
+        self
+    }
+}
+
+// most severe first, then in source order so the output is stable
+pub fn sort_findings(findings: &mut [Finding]) {
+    findings.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.location.cmp(&b.location))
+            .then_with(|| a.rule_id.cmp(&b.rule_id))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(rule_id: &str, severity: Severity, line: usize) -> Finding {
+        let location = Location {
+            file: "lib.rs".to_string(),
+            start_line: line,
+            start_column: 1,
+            end_line: line,
+            end_column: 1,
+        };
+        Finding::new(rule_id, severity, location, String::new())
+    }
+
+    #[test]
+    fn locations_are_one_based() {
+        let syntax_tree = syn::parse_file("fn first() {}\n    fn second() {}\n").unwrap();
+        let syn::Item::Fn(second) = &syntax_tree.items[1] else {
+            panic!("second item is not a function");
+        };
+        let location = Location::from_span("lib.rs", second.sig.ident.span());
+        assert_eq!((location.start_line, location.start_column), (2, 8));
+        assert_eq!((location.end_line, location.end_column), (2, 14));
+        assert_eq!(location.file, "lib.rs");
+    }
+
+    #[test]
+    fn findings_sort_by_severity_then_location() {
+        let mut findings = vec![
+            finding("b-rule", Severity::Low, 3),
+            finding("a-rule", Severity::High, 9),
+            finding("c-rule", Severity::Low, 1),
+            finding("a-rule", Severity::Low, 3),
+        ];
+        sort_findings(&mut findings);
+        let order: Vec<(&str, usize)> = findings
+            .iter()
+            .map(|finding| (finding.rule_id.as_str(), finding.location.start_line))
+            .collect();
+        assert_eq!(order, vec![("a-rule", 9), ("c-rule", 1), ("a-rule", 3), ("b-rule", 3)]);
+    }
+
+    #[test]
+    fn builders_fill_in_the_report_fields() {
+        let finding = finding("rule", Severity::High, 1)
+            .with_confidence(Confidence::High)
+            .with_function("withdraw")
+            .with_remediation("check the signer");
+        assert_eq!(finding.confidence, Confidence::High);
+        assert_eq!(finding.function_name, "withdraw");
+        assert_eq!(finding.remediation, "check the signer");
+
+        let json = serde_json::to_value(&finding).unwrap();
+        assert_eq!(json["rule_id"], "rule");
+        assert_eq!(json["severity"], "High");
+        assert_eq!(json["location"]["start_line"], 1);
+    }
+}
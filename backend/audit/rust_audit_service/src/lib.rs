@@ -0,0 +1,55 @@
+pub mod anchor;
+pub mod cfg;
+pub mod dataflow;
+pub mod detectors;
+pub mod evidence;
+pub mod finding;
+pub mod macros;
+pub mod program;
+pub mod syntax;
+pub mod taint;
+pub mod walk;
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use detectors::{AnalysisContext, DetectorRegistry};
+use finding::{sort_findings, Finding};
+use program::{LoadError, Program};
+
+// Resolves a caller supplied path against `root` and refuses anything that ends up
+// outside of it once symlinks and `..` are resolved
+pub fn resolve_contract_path(root: &Path, requested: &str) -> io::Result<PathBuf> {
+    let root = root.canonicalize()?;
+    let path = root.join(requested).canonicalize()?;
+    if !path.starts_with(&root) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("'{}' is outside of the contracts root", requested),
+        ));
+    }
+    Ok(path)
+}
+
+pub fn analyze_program<F: Fn(&str) -> bool>(
+    program: &Program,
+    registry: &DetectorRegistry,
+    selected: F,
+) -> Vec<Finding> {
+    let ctx = AnalysisContext::new(program);
+
+    let mut issues = registry.run_filtered(&ctx, selected);
+    sort_findings(&mut issues);
+    issues
+}
+
+// `file_name` is only used to label findings, nothing is read from disk
+pub fn analyze_source<F: Fn(&str) -> bool>(
+    source: &str,
+    file_name: &str,
+    registry: &DetectorRegistry,
+    selected: F,
+) -> Result<Vec<Finding>, LoadError> {
+    let program = Program::from_source(source, file_name)?;
+    Ok(analyze_program(&program, registry, selected))
+}
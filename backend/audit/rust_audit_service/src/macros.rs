@@ -0,0 +1,119 @@
+use proc_macro2::Span;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
+use syn::{parse_quote_spanned, Expr, ExprIf, File, Lit, Macro, Stmt, Token};
+
+// Rewrites the invocations of well known check, error and logging macros into the code they
+// stand for, so that every analysis sees their arguments like any other expression, e.g.
+// `require!(cond, err)` becomes `if !(cond) { return Err(err); }`. invocations whose
+// arguments do not parse as expressions are left as they are
+pub fn expand(file: &mut File) {
+    Expander.visit_file_mut(file);
+}
+
+struct Expander;
+
+impl VisitMut for Expander {
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        if let Stmt::Macro(stmt_macro) = stmt {
+            if let Some(expr) = expand_macro(&stmt_macro.mac) {
+                *stmt = Stmt::Expr(expr, stmt_macro.semi_token);
+            }
+        }
+        visit_mut::visit_stmt_mut(self, stmt);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Expr::Macro(expr_macro) = expr {
+            if let Some(expanded) = expand_macro(&expr_macro.mac) {
+                *expr = expanded;
+            }
+        }
+        visit_mut::visit_expr_mut(self, expr);
+    }
+}
+
+fn expand_macro(mac: &Macro) -> Option<Expr> {
+    let name = mac.path.segments.last()?.ident.to_string();
+    let args: Vec<Expr> = mac
+        .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+        .ok()?
+        .into_iter()
+        .collect();
+    let span = mac.span();
+
+    match (name.as_str(), args.as_slice()) {
+        ("require", [cond, error]) => Some(guard(negate(cond, span), error_exit(error, span), span)),
+        ("require_keys_eq", [left, right, rest @ ..]) => {
+            let exit = match rest {
+                [error] => error_exit(error, span),
+                _ => parse_quote_spanned!(span=> Err(ErrorCode::RequireKeysEqViolated)),
+            };
+            Some(guard(differ(left, right, span), exit, span))
+        }
+        ("require_keys_neq", [left, right, rest @ ..]) => {
+            let exit = match rest {
+                [error] => error_exit(error, span),
+                _ => parse_quote_spanned!(span=> Err(ErrorCode::RequireKeysNeqViolated)),
+            };
+            Some(guard(same(left, right, span), exit, span))
+        }
+        // a failing assertion aborts the transaction the same way an error does
+        ("assert", [cond, ..]) => Some(guard(negate(cond, span), assertion_exit(span), span)),
+        ("assert_eq", [left, right, ..]) => Some(guard(differ(left, right, span), assertion_exit(span), span)),
+        ("assert_ne", [left, right, ..]) => Some(guard(same(left, right, span), assertion_exit(span), span)),
+        // `err!(ErrorCode::Unauthorized)` is `Err(..)` and `error!(..)` the error itself
+        ("err", [error]) => Some(parse_quote_spanned!(span=> Err(#error))),
+        // `log::error!` shares the name but takes a format string
+        ("error", [error]) if !is_str_literal(error) => Some(error.clone()),
+        // only the values are of interest, the format string is not
+        ("msg", [format, values @ ..]) if is_str_literal(format) && !values.is_empty() => {
+            Some(parse_quote_spanned!(span=> (#(#values,)*)))
+        }
+        _ => None,
+    }
+}
+
+// `if <failed> { return <exit>; }`
+fn guard(failed: Expr, exit: Expr, span: Span) -> Expr {
+    let mut expr_if: ExprIf = parse_quote_spanned!(span=> if failed { return #exit; });
+    *expr_if.cond = failed;
+    Expr::If(expr_if)
+}
+
+fn negate(cond: &Expr, span: Span) -> Expr {
+    parse_quote_spanned!(span=> !(#cond))
+}
+
+// `left != right`, built in place so that the operands keep their grouping
+fn differ(left: &Expr, right: &Expr, span: Span) -> Expr {
+    let mut comparison: Expr = parse_quote_spanned!(span=> left != right);
+    if let Expr::Binary(expr_binary) = &mut comparison {
+        *expr_binary.left = left.clone();
+        *expr_binary.right = right.clone();
+    }
+    comparison
+}
+
+// `left == right`, the failing condition of `require_keys_neq!` and `assert_ne!`
+fn same(left: &Expr, right: &Expr, span: Span) -> Expr {
+    let mut comparison: Expr = parse_quote_spanned!(span=> left == right);
+    if let Expr::Binary(expr_binary) = &mut comparison {
+        *expr_binary.left = left.clone();
+        *expr_binary.right = right.clone();
+    }
+    comparison
+}
+
+fn error_exit(error: &Expr, span: Span) -> Expr {
+    parse_quote_spanned!(span=> Err(#error))
+}
+
+fn assertion_exit(span: Span) -> Expr {
+    parse_quote_spanned!(span=> Err(Default::default()))
+}
+
+fn is_str_literal(expr: &Expr) -> bool {
+    matches!(expr, Expr::Lit(expr_lit) if matches!(expr_lit.lit, Lit::Str(_)))
+}
@@ -0,0 +1,261 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use syn::{parse_file, Attribute, Expr, File, Item, Lit, Meta};
+
+use crate::macros;
+
+// Crate roots we look for, in order, relative to the crate directory
+const CRATE_ROOTS: [&str; 4] = ["src/lib.rs", "src/main.rs", "lib.rs", "main.rs"];
+
+pub struct SourceFile {
+    // path relative to the crate root, used as the `file` of findings
+    pub path: String,
+    // module the file is mounted at, empty for the crate root
+    pub module_path: Vec<String>,
+    pub syntax_tree: File,
+}
+
+// Every source file of one program, in the order the module tree was walked
+pub struct Program {
+    pub files: Vec<SourceFile>,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(String, io::Error),
+    Parse(String, syn::Error),
+    NoCrateRoot,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(path, e) => write!(f, "{}: {}", path, e),
+            LoadError::Parse(path, e) => {
+                let start = e.span().start();
+                write!(f, "{}:{}:{}: {}", path, start.line, start.column + 1, e)
+            }
+            LoadError::NoCrateRoot => write!(f, "no src/lib.rs or src/main.rs found"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl Program {
+    pub fn from_source(source: &str, file_name: &str) -> Result<Program, LoadError> {
+        let mut syntax_tree = parse_file(source).map_err(|e| LoadError::Parse(file_name.to_string(), e))?;
+        macros::expand(&mut syntax_tree);
+        Ok(Program {
+            files: vec![SourceFile {
+                path: file_name.to_string(),
+                module_path: Vec::new(),
+                syntax_tree,
+            }],
+        })
+    }
+
+    // `path` is a crate directory or its Cargo.toml. modules are followed from
+    // src/lib.rs (or src/main.rs) and may not leave the crate directory
+    pub fn load_crate(path: &Path) -> Result<Program, LoadError> {
+        let dir = if path.is_dir() {
+            path.to_path_buf()
+        } else {
+            path.parent().map(Path::to_path_buf).unwrap_or_default()
+        };
+        let root = dir
+            .canonicalize()
+            .map_err(|e| LoadError::Io(dir.display().to_string(), e))?;
+
+        load_program(|relative| {
+            let full = root.join(relative).canonicalize()?;
+            if !full.starts_with(&root) {
+                return Err(io::Error::new(io::ErrorKind::PermissionDenied, "module is outside of the crate"));
+            }
+            fs::read_to_string(full)
+        })
+    }
+
+    // Same as `load_crate` for a crate sent inline, keyed by path relative to the crate root
+    pub fn from_files(files: &BTreeMap<String, String>) -> Result<Program, LoadError> {
+        let files: BTreeMap<PathBuf, &String> = files
+            .iter()
+            .filter_map(|(path, source)| Some((normalize(Path::new(path))?, source)))
+            .collect();
+
+        load_program(|relative| {
+            files
+                .get(relative)
+                .map(|source| source.to_string())
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        })
+    }
+}
+
+struct ModuleLoader<R> {
+    read: R,
+    visited: HashSet<PathBuf>,
+    files: Vec<SourceFile>,
+}
+
+// A `mod foo;` that still has to be read: the candidate files in order of
+// preference and the module path it gets mounted at
+struct PendingModule {
+    candidates: Vec<PathBuf>,
+    module_path: Vec<String>,
+    // where its own `mod bar;` declarations are looked up
+    mod_dir: PathBuf,
+}
+
+fn load_program<R: Fn(&Path) -> io::Result<String>>(read: R) -> Result<Program, LoadError> {
+    let mut loader = ModuleLoader {
+        read,
+        visited: HashSet::new(),
+        files: Vec::new(),
+    };
+
+    let root = CRATE_ROOTS
+        .iter()
+        .map(PathBuf::from)
+        .find_map(|path| (loader.read)(&path).ok().map(|source| (path, source)));
+    let Some((root_path, source)) = root else {
+        return Err(LoadError::NoCrateRoot);
+    };
+
+    let mod_dir = root_path.parent().map(Path::to_path_buf).unwrap_or_default();
+    loader.load_file(root_path, source, Vec::new(), mod_dir)?;
+
+    Ok(Program { files: loader.files })
+}
+
+impl<R: Fn(&Path) -> io::Result<String>> ModuleLoader<R> {
+    fn load_file(
+        &mut self,
+        path: PathBuf,
+        source: String,
+        module_path: Vec<String>,
+        mod_dir: PathBuf,
+    ) -> Result<(), LoadError> {
+        let display_path = path.to_string_lossy().replace('\\', "/");
+        if !self.visited.insert(path.clone()) {
+            return Ok(());
+        }
+
+        let mut syntax_tree = parse_file(&source).map_err(|e| LoadError::Parse(display_path.clone(), e))?;
+        macros::expand(&mut syntax_tree);
+
+        let mut pending = Vec::new();
+        let file_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        collect_modules(&syntax_tree.items, &file_dir, &mod_dir, &module_path, &mut pending);
+
+        self.files.push(SourceFile {
+            path: display_path,
+            module_path,
+            syntax_tree,
+        });
+
+        for module in pending {
+            self.load_module(module)?;
+        }
+        Ok(())
+    }
+
+    fn load_module(&mut self, module: PendingModule) -> Result<(), LoadError> {
+        for candidate in module.candidates {
+            let Some(candidate) = normalize(&candidate) else {
+                continue;
+            };
+            match (self.read)(&candidate) {
+                Ok(source) => return self.load_file(candidate, source, module.module_path, module.mod_dir),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(LoadError::Io(candidate.to_string_lossy().into_owned(), e)),
+            }
+        }
+        // usually a `#[cfg(test)] mod tests;` or a module generated at build time,
+        // neither of which we can see
+        Ok(())
+    }
+}
+
+// `path_base` is where `#[path]` attributes are resolved from, `mod_dir` is where
+// plain `mod foo;` declarations are looked up. both move down a level for inline modules
+fn collect_modules(
+    items: &[Item],
+    path_base: &Path,
+    mod_dir: &Path,
+    module_path: &[String],
+    pending: &mut Vec<PendingModule>,
+) {
+    for item in items {
+        if let Item::Mod(item_mod) = item {
+            let name = item_mod.ident.to_string();
+            let mut child_path = module_path.to_vec();
+            child_path.push(name.clone());
+
+            match &item_mod.content {
+                Some((_, inline_items)) => {
+                    let inline_dir = mod_dir.join(&name);
+                    let inline_base = match path_attribute(&item_mod.attrs) {
+                        Some(path) => path_base.join(path),
+                        None => inline_dir.clone(),
+                    };
+                    collect_modules(inline_items, &inline_base, &inline_base, &child_path, pending);
+                }
+                None => match path_attribute(&item_mod.attrs) {
+                    Some(path) => {
+                        let file = path_base.join(path);
+                        // files loaded through #[path] resolve their children like a mod.rs
+                        let child_dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+                        pending.push(PendingModule {
+                            candidates: vec![file],
+                            module_path: child_path,
+                            mod_dir: child_dir,
+                        });
+                    }
+                    None => pending.push(PendingModule {
+                        candidates: vec![
+                            mod_dir.join(format!("{}.rs", name)),
+                            mod_dir.join(&name).join("mod.rs"),
+                        ],
+                        module_path: child_path,
+                        mod_dir: mod_dir.join(&name),
+                    }),
+                },
+            }
+        }
+    }
+}
+
+fn path_attribute(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::NameValue(name_value) if name_value.path.is_ident("path") => match &name_value.value {
+            Expr::Lit(expr_lit) => match &expr_lit.lit {
+                Lit::Str(lit_str) => Some(lit_str.value()),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+// Lexically resolves `.` and `..`, refusing paths that climb above the crate root
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalized)
+}
@@ -0,0 +1,384 @@
+use proc_macro2::Span;
+use quote::ToTokens;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Block, Expr, Item, Member, Pat, Path, Stmt, Token, Type};
+
+use crate::dataflow::Dataflow;
+use crate::walk::FnItem;
+
+// Calls `f` on every expression in `block`, outer expressions before the ones they
+// contain. nested items are skipped, the walker reports them as functions of their own
+pub fn for_each_expr<'a, F: FnMut(&'a Expr)>(block: &'a Block, f: F) {
+    struct Exprs<F> {
+        f: F,
+    }
+
+    impl<'a, F: FnMut(&'a Expr)> Visit<'a> for Exprs<F> {
+        fn visit_expr(&mut self, expr: &'a Expr) {
+            (self.f)(expr);
+            visit::visit_expr(self, expr);
+        }
+
+        fn visit_item(&mut self, _item: &'a Item) {}
+    }
+
+    Exprs { f }.visit_block(block);
+}
+
+// Calls `f` on every macro invocation in `block`, statement and expression position alike
+pub fn for_each_macro<'a, F: FnMut(&'a syn::Macro)>(block: &'a Block, f: F) {
+    struct Macros<F> {
+        f: F,
+    }
+
+    impl<'a, F: FnMut(&'a syn::Macro)> Visit<'a> for Macros<F> {
+        fn visit_macro(&mut self, mac: &'a syn::Macro) {
+            (self.f)(mac);
+        }
+
+        fn visit_item(&mut self, _item: &'a Item) {}
+    }
+
+    Macros { f }.visit_block(block);
+}
+
+pub fn member_name(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+// Source text of an expression for messages, e.g. `user.key.as_ref()`
+pub fn render(expr: &Expr) -> String {
+    let mut text = expr.to_token_stream().to_string();
+    let spacing = [
+        (" . ", "."),
+        (" :: ", "::"),
+        (" (", "("),
+        ("( ", "("),
+        (" )", ")"),
+        (" [", "["),
+        ("[ ", "["),
+        (" ]", "]"),
+        ("& ", "&"),
+        (" ,", ","),
+        ("! ", "!"),
+        ("* * ", "**"),
+        (" ?", "?"),
+    ];
+    for (from, to) in spacing {
+        text = text.replace(from, to);
+    }
+    match text.strip_prefix("* ") {
+        Some(rest) => format!("*{}", rest),
+        None => text,
+    }
+}
+
+// Strips `&`, `&mut`, `*`, parens and `?` off an expression
+pub fn strip(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Reference(expr_ref) => strip(&expr_ref.expr),
+        Expr::Unary(expr_unary) if matches!(expr_unary.op, syn::UnOp::Deref(_)) => strip(&expr_unary.expr),
+        Expr::Paren(expr_paren) => strip(&expr_paren.expr),
+        Expr::Try(expr_try) => strip(&expr_try.expr),
+        Expr::Group(expr_group) => strip(&expr_group.expr),
+        _ => expr,
+    }
+}
+
+// `x` for a single identifier path
+pub fn path_ident(expr: &Expr) -> Option<String> {
+    match strip(expr) {
+        Expr::Path(expr_path) => expr_path.path.get_ident().map(|ident| ident.to_string()),
+        _ => None,
+    }
+}
+
+// Last path segment of a called function, or the method name of a method call
+pub fn call_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Call(call) => match &*call.func {
+            Expr::Path(expr_path) => expr_path.path.segments.last().map(|segment| segment.ident.to_string()),
+            _ => None,
+        },
+        Expr::MethodCall(method_call) => Some(method_call.method.to_string()),
+        _ => None,
+    }
+}
+
+// Arguments of a call, including the receiver of a method call
+pub fn call_args(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::Call(call) => call.args.iter().collect(),
+        Expr::MethodCall(method_call) => std::iter::once(&*method_call.receiver)
+            .chain(method_call.args.iter())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// The binding an access path starts from: `vault` for `vault.key`, `**vault.lamports.borrow_mut()`
+// or `&vault.data`. Anchor's `ctx.accounts.vault` is treated as `vault`
+pub fn root_ident(expr: &Expr) -> Option<String> {
+    match strip(expr) {
+        Expr::Path(expr_path) => expr_path.path.get_ident().map(|ident| ident.to_string()),
+        Expr::Field(expr_field) => {
+            if let Expr::Field(inner) = strip(&expr_field.base) {
+                if member_name(&inner.member) == "accounts" {
+                    return Some(member_name(&expr_field.member));
+                }
+            }
+            root_ident(&expr_field.base)
+        }
+        Expr::MethodCall(method_call) => root_ident(&method_call.receiver),
+        Expr::Index(expr_index) => root_ident(&expr_index.expr),
+        _ => None,
+    }
+}
+
+// The account whose key this is: `vault` for `vault.key`, `*vault.key`, `vault.key()`
+// or `vault.key.clone()`
+pub fn key_owner(expr: &Expr) -> Option<String> {
+    match strip(expr) {
+        Expr::Field(expr_field) if member_name(&expr_field.member) == "key" => root_ident(&expr_field.base),
+        Expr::MethodCall(method_call) if method_call.method == "key" => root_ident(&method_call.receiver),
+        Expr::MethodCall(method_call) if method_call.method == "clone" => key_owner(&method_call.receiver),
+        _ => None,
+    }
+}
+
+// True when `expr` reads `<binding>.<field>` somewhere, e.g. `authority.is_signer`
+pub fn mentions_field(expr: &Expr, binding: &str, field: &str) -> bool {
+    let mut found = false;
+    let mut check = |expr: &Expr| {
+        if let Expr::Field(expr_field) = expr {
+            if member_name(&expr_field.member) == field && root_ident(&expr_field.base).as_deref() == Some(binding) {
+                found = true;
+            }
+        }
+    };
+    let mut visitor = ExprVisitor { f: &mut check };
+    visitor.visit_expr(expr);
+    found
+}
+
+// True when `expr` contains a use of the plain identifier `binding`
+pub fn mentions_ident(expr: &Expr, binding: &str) -> bool {
+    let mut found = false;
+    let mut check = |expr: &Expr| {
+        if let Expr::Path(expr_path) = expr {
+            if expr_path.path.is_ident(binding) {
+                found = true;
+            }
+        }
+    };
+    let mut visitor = ExprVisitor { f: &mut check };
+    visitor.visit_expr(expr);
+    found
+}
+
+// True when `a` and `b` read the same value as far as the syntax tells: the same binding,
+// field path, literal or argument-less method call, through references, derefs and casts,
+// e.g. `vault.balance` and `*vault.balance as u128`
+pub fn same_value(a: &Expr, b: &Expr) -> bool {
+    let uncast = |expr| match strip(expr) {
+        Expr::Cast(expr_cast) => strip(&expr_cast.expr),
+        expr => expr,
+    };
+    match (uncast(a), uncast(b)) {
+        (Expr::Path(a), Expr::Path(b)) => {
+            let idents = |path: &Path| path.segments.iter().map(|segment| segment.ident.clone()).collect::<Vec<_>>();
+            idents(&a.path) == idents(&b.path)
+        }
+        (Expr::Lit(a), Expr::Lit(b)) => a.lit.to_token_stream().to_string() == b.lit.to_token_stream().to_string(),
+        (Expr::Field(a), Expr::Field(b)) => a.member == b.member && same_value(&a.base, &b.base),
+        (Expr::Index(a), Expr::Index(b)) => same_value(&a.expr, &b.expr) && same_value(&a.index, &b.index),
+        (Expr::MethodCall(a), Expr::MethodCall(b)) => {
+            a.method == b.method && a.args.is_empty() && b.args.is_empty() && same_value(&a.receiver, &b.receiver)
+        }
+        _ => false,
+    }
+}
+
+// True when `pred` holds for `expr` or any expression inside it
+pub fn contains_expr<F: FnMut(&Expr) -> bool>(expr: &Expr, mut pred: F) -> bool {
+    let mut found = false;
+    let mut check = |expr: &Expr| {
+        if !found && pred(expr) {
+            found = true;
+        }
+    };
+    let mut visitor = ExprVisitor { f: &mut check };
+    visitor.visit_expr(expr);
+    found
+}
+
+struct ExprVisitor<'f, F> {
+    f: &'f mut F,
+}
+
+impl<'a, F: FnMut(&Expr)> Visit<'a> for ExprVisitor<'_, F> {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        (self.f)(expr);
+        visit::visit_expr(self, expr);
+    }
+}
+
+// A block leaves the function on every path through its last statement: `return`,
+// `Err(..)?`, a trailing `Err(..)`, `panic!` and friends
+pub fn diverges(block: &Block) -> bool {
+    match block.stmts.last() {
+        Some(Stmt::Expr(expr, _)) => expr_diverges(expr),
+        Some(Stmt::Macro(stmt_macro)) => is_panic_macro(&stmt_macro.mac),
+        _ => false,
+    }
+}
+
+pub fn expr_diverges(expr: &Expr) -> bool {
+    match expr {
+        Expr::Return(_) => true,
+        Expr::Try(expr_try) => is_err_constructor(&expr_try.expr),
+        Expr::Call(_) => is_err_constructor(expr),
+        Expr::Macro(expr_macro) => is_panic_macro(&expr_macro.mac),
+        Expr::Block(expr_block) => diverges(&expr_block.block),
+        Expr::If(expr_if) => {
+            diverges(&expr_if.then_branch)
+                && expr_if
+                    .else_branch
+                    .as_ref()
+                    .is_some_and(|(_, else_expr)| expr_diverges(else_expr))
+        }
+        _ => false,
+    }
+}
+
+fn is_err_constructor(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(call) => matches!(&*call.func, Expr::Path(expr_path) if expr_path.path.is_ident("Err")),
+        _ => false,
+    }
+}
+
+pub fn is_panic_macro(mac: &syn::Macro) -> bool {
+    mac.path.segments.last().is_some_and(|segment| {
+        ["panic", "unreachable", "unimplemented", "todo"].contains(&segment.ident.to_string().as_str())
+    })
+}
+
+// `if` conditions in `block` where one branch leaves the function, so the
+// condition acts as a check on the code that follows
+pub fn guard_conditions(block: &Block) -> Vec<&Expr> {
+    let mut guards = Vec::new();
+    for_each_expr(block, |expr| {
+        if let Expr::If(expr_if) = expr {
+            let else_diverges = match &expr_if.else_branch {
+                Some((_, else_expr)) => expr_diverges(else_expr),
+                None => false,
+            };
+            if diverges(&expr_if.then_branch) || else_diverges {
+                guards.push(&*expr_if.cond);
+            }
+        }
+    });
+    guards
+}
+
+// (line, column) of the start of `span`, for "happens before" comparisons inside one file
+pub fn position(span: Span) -> (usize, usize) {
+    let start = span.start();
+    (start.line, start.column)
+}
+
+pub fn starts_before(a: &impl Spanned, b: &impl Spanned) -> bool {
+    position(a.span()) < position(b.span())
+}
+
+// True when `inner` lies within `outer` in the same file
+pub fn encloses(outer: &impl Spanned, inner: &impl Spanned) -> bool {
+    let (outer, inner) = (outer.span(), inner.span());
+    let end = |span: Span| (span.end().line, span.end().column);
+    position(outer) <= position(inner) && end(inner) <= end(outer)
+}
+
+// Names bound to an `AccountInfo` in a function: parameters typed `AccountInfo`,
+// `let x = next_account_info(iter)?`, `let x = &accounts[i]` and their aliases
+pub fn account_bindings(func: &FnItem) -> Vec<(String, Span)> {
+    Dataflow::new(func).bindings()
+}
+
+// Calls `f` on every `let` in `block`, nested blocks included
+pub fn visit_locals<'a, F: FnMut(&'a syn::Local)>(block: &'a Block, f: F) {
+    struct Locals<F> {
+        f: F,
+    }
+
+    impl<'a, F: FnMut(&'a syn::Local)> Visit<'a> for Locals<F> {
+        fn visit_local(&mut self, local: &'a syn::Local) {
+            (self.f)(local);
+            visit::visit_local(self, local);
+        }
+
+        fn visit_item(&mut self, _item: &'a Item) {}
+    }
+
+    Locals { f }.visit_block(block);
+}
+
+// Initializer of the last `let <name> = ..` that starts before `at`
+pub fn local_init<'a>(block: &'a Block, name: &str, at: &impl Spanned) -> Option<&'a Expr> {
+    let mut init = None;
+    visit_locals(block, |local| {
+        if let (Pat::Ident(pat_ident), Some(local_init)) = (strip_pat(&local.pat), &local.init) {
+            if pat_ident.ident == name && starts_before(local, at) {
+                init = Some(&*local_init.expr);
+            }
+        }
+    });
+    init
+}
+
+// True when `pat` binds `name`, also inside tuple and struct patterns
+pub fn pat_binds(pat: &Pat, name: &str) -> bool {
+    match strip_pat(pat) {
+        Pat::Ident(pat_ident) => pat_ident.ident == name,
+        Pat::Tuple(pat_tuple) => pat_tuple.elems.iter().any(|elem| pat_binds(elem, name)),
+        Pat::TupleStruct(pat_tuple_struct) => pat_tuple_struct.elems.iter().any(|elem| pat_binds(elem, name)),
+        Pat::Struct(pat_struct) => pat_struct.fields.iter().any(|field| pat_binds(&field.pat, name)),
+        _ => false,
+    }
+}
+
+pub fn strip_pat(pat: &Pat) -> &Pat {
+    match pat {
+        Pat::Type(pat_type) => strip_pat(&pat_type.pat),
+        Pat::Reference(pat_ref) => strip_pat(&pat_ref.pat),
+        _ => pat,
+    }
+}
+
+pub fn is_account_info_type(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => is_account_info_type(&reference.elem),
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "AccountInfo"),
+        _ => false,
+    }
+}
+
+// Last path segment of every trait in `#[derive(..)]`, e.g. `BorshDeserialize`
+pub fn derives(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .filter_map(|attr| attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated).ok())
+        .flatten()
+        .filter_map(|path| path.segments.last().map(|segment| segment.ident.to_string()))
+        .collect()
+}
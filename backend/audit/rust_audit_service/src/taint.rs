@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{BinOp, Expr, ExprAssign, ExprBinary, ExprForLoop, ExprLet, ExprMatch, Item, Local, Pat, PatIdent};
+
+use crate::finding::TraceStep;
+use crate::syntax::{call_name, path_ident, position, render};
+use crate::walk::FnItem;
+
+// calls that cap a value, so whatever comes out of them is no longer attacker controlled
+const SANITIZERS: [&str; 2] = ["min", "clamp"];
+
+// rendered expressions in trace notes are cut to this many characters
+const MAX_NOTE_EXPR: usize = 80;
+
+pub type Trace = Vec<TraceStep>;
+
+// One definition of a binding and, when it holds attacker controlled data, how it got there
+struct TaintDef {
+    name: String,
+    // the definition is only seen by code that starts after this position
+    visible: (usize, usize),
+    trace: Option<Trace>,
+}
+
+// Attacker controlled bindings of one function: the seeded parameters and everything
+// computed from them, followed through `let`s, assignments, patterns and loops in
+// source order. a later definition that reads nothing tainted clears the taint
+pub struct Taint {
+    defs: Vec<TaintDef>,
+}
+
+impl Taint {
+    // `seeds` maps the parameters known to carry instruction data to how they got it
+    pub fn new(func: &FnItem, seeds: &HashMap<String, Trace>) -> Self {
+        let mut taint = Taint {
+            defs: seeds
+                .iter()
+                .map(|(name, trace)| TaintDef {
+                    name: name.clone(),
+                    visible: (0, 0),
+                    trace: Some(trace.clone()),
+                })
+                .collect(),
+        };
+        Collector {
+            func,
+            taint: &mut taint,
+        }
+        .visit_block(func.block);
+        taint
+    }
+
+    // How the first attacker controlled binding `expr` reads got its data, if there is one
+    pub fn trace_of(&self, expr: &Expr) -> Option<Trace> {
+        unsanitized_reads(expr)
+            .into_iter()
+            .find_map(|read| self.read(read).and_then(|def| def.trace.clone()))
+    }
+
+    // Names of the attacker controlled bindings `expr` reads
+    pub fn tainted_names(&self, expr: &Expr) -> Vec<String> {
+        let mut names = Vec::new();
+        for read in unsanitized_reads(expr) {
+            if let Some(def) = self.read(read).filter(|def| def.trace.is_some()) {
+                if !names.contains(&def.name) {
+                    names.push(def.name.clone());
+                }
+            }
+        }
+        names
+    }
+
+    // The definition a path expression reads
+    fn read(&self, expr: &Expr) -> Option<&TaintDef> {
+        let Expr::Path(_) = expr else {
+            return None;
+        };
+        let name = path_ident(expr)?;
+        let at = position(expr.span());
+        self.defs
+            .iter()
+            .filter(|def| def.name == name && def.visible <= at)
+            .max_by_key(|def| def.visible)
+    }
+}
+
+struct Collector<'f, 'a> {
+    func: &'f FnItem<'a>,
+    taint: &'f mut Taint,
+}
+
+impl Collector<'_, '_> {
+    // Binds every name in `pat` from the end of `span` on, tainted when `source` is
+    fn bind(&mut self, pat: &Pat, source: Option<&Expr>, span: Span) {
+        let mut names = Names(Vec::new());
+        names.visit_pat(pat);
+        let trace = source.and_then(|source| {
+            let mut trace = self.taint.trace_of(source)?;
+            trace.push(self.step(span, &names.0, source));
+            Some(trace)
+        });
+        for name in names.0 {
+            self.taint.defs.push(TaintDef {
+                name,
+                visible: end(span),
+                trace: trace.clone(),
+            });
+        }
+    }
+
+    fn define(&mut self, name: String, trace: Option<Trace>, visible: (usize, usize)) {
+        self.taint.defs.push(TaintDef { name, visible, trace });
+    }
+
+    fn step(&self, span: Span, names: &[String], source: &Expr) -> TraceStep {
+        TraceStep {
+            location: self.func.location(span),
+            note: format!("'{}' is computed from `{}`", names.join("', '"), short(source)),
+        }
+    }
+}
+
+impl<'a> Visit<'a> for Collector<'_, '_> {
+    fn visit_local(&mut self, local: &'a Local) {
+        visit::visit_local(self, local);
+        let init = local.init.as_ref().map(|init| &*init.expr);
+        self.bind(&local.pat, init, local.span());
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'a ExprAssign) {
+        visit::visit_expr_assign(self, assign);
+        if let (Expr::Path(_), Some(name)) = (&*assign.left, path_ident(&assign.left)) {
+            let trace = self.taint.trace_of(&assign.right).map(|mut trace| {
+                trace.push(self.step(assign.span(), std::slice::from_ref(&name), &assign.right));
+                trace
+            });
+            self.define(name, trace, end(assign.span()));
+        }
+    }
+
+    // `total += amount` taints `total`, and keeps it tainted otherwise
+    fn visit_expr_binary(&mut self, binary: &'a ExprBinary) {
+        visit::visit_expr_binary(self, binary);
+        let compound = matches!(
+            binary.op,
+            BinOp::AddAssign(_) | BinOp::SubAssign(_) | BinOp::MulAssign(_) | BinOp::ShlAssign(_)
+        );
+        if let (true, Expr::Path(_), Some(name)) = (compound, &*binary.left, path_ident(&binary.left)) {
+            if let Some(mut trace) = self.taint.trace_of(&binary.right) {
+                trace.push(self.step(binary.span(), std::slice::from_ref(&name), &binary.right));
+                self.define(name, Some(trace), end(binary.span()));
+            }
+        }
+    }
+
+    fn visit_expr_for_loop(&mut self, for_loop: &'a ExprForLoop) {
+        self.visit_expr(&for_loop.expr);
+        self.bind(&for_loop.pat, Some(&for_loop.expr), for_loop.pat.span());
+        self.visit_block(&for_loop.body);
+    }
+
+    // `match instruction { Instruction::Withdraw { amount } => .. }`
+    fn visit_expr_match(&mut self, expr_match: &'a ExprMatch) {
+        self.visit_expr(&expr_match.expr);
+        for arm in &expr_match.arms {
+            self.bind(&arm.pat, Some(&expr_match.expr), arm.pat.span());
+            if let Some((_, guard)) = &arm.guard {
+                self.visit_expr(guard);
+            }
+            self.visit_expr(&arm.body);
+        }
+    }
+
+    // `if let Some(amount) = ..` and `while let ..`
+    fn visit_expr_let(&mut self, expr_let: &'a ExprLet) {
+        self.visit_expr(&expr_let.expr);
+        self.bind(&expr_let.pat, Some(&expr_let.expr), expr_let.pat.span());
+    }
+
+    fn visit_item(&mut self, _item: &'a Item) {}
+}
+
+// The paths `expr` reads, in source order, except those feeding a sanitizer: in
+// `amount + fee.min(10)` only `amount` still reaches the result
+fn unsanitized_reads(expr: &Expr) -> Vec<&Expr> {
+    struct Reads<'e>(Vec<&'e Expr>);
+
+    impl<'e> Visit<'e> for Reads<'e> {
+        fn visit_expr(&mut self, expr: &'e Expr) {
+            if call_name(expr).is_some_and(|name| SANITIZERS.contains(&name.as_str())) {
+                return;
+            }
+            if let Expr::Path(_) = expr {
+                self.0.push(expr);
+            }
+            visit::visit_expr(self, expr);
+        }
+    }
+
+    let mut reads = Reads(Vec::new());
+    reads.visit_expr(expr);
+    reads.0
+}
+
+struct Names(Vec<String>);
+
+impl<'p> Visit<'p> for Names {
+    fn visit_pat_ident(&mut self, pat_ident: &'p PatIdent) {
+        self.0.push(pat_ident.ident.to_string());
+        visit::visit_pat_ident(self, pat_ident);
+    }
+}
+
+fn end(span: Span) -> (usize, usize) {
+    (span.end().line, span.end().column)
+}
+
+fn short(expr: &Expr) -> String {
+    let text = render(expr);
+    if text.chars().count() <= MAX_NOTE_EXPR {
+        return text;
+    }
+    let cut: String = text.chars().take(MAX_NOTE_EXPR).collect();
+    format!("{}..", cut)
+}
@@ -0,0 +1,193 @@
+use proc_macro2::Span;
+use syn::visit::{self, Visit};
+use syn::{Attribute, Block, ImplItem, Item, Signature, TraitItem, Type};
+
+use crate::finding::{Finding, Location, Severity};
+use crate::program::{Program, SourceFile};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FnKind {
+    // `fn` at module level, including inside inline `mod` blocks
+    Free,
+    // `fn` in an inherent or trait `impl` block
+    Method,
+    // default body of a method declared in a `trait`
+    TraitDefault,
+    // `fn` item declared inside another function's body
+    Nested,
+}
+
+// One function body somewhere in the program, with enough context to report on it
+pub struct FnItem<'a> {
+    pub file: &'a SourceFile,
+    pub module_path: Vec<String>,
+    // type or trait the method belongs to, `None` for free and nested functions
+    pub owner: Option<String>,
+    pub kind: FnKind,
+    pub attrs: &'a [Attribute],
+    pub sig: &'a Signature,
+    pub block: &'a Block,
+}
+
+impl FnItem<'_> {
+    pub fn name(&self) -> String {
+        self.sig.ident.to_string()
+    }
+
+    // `Function` or `Method`, for messages
+    pub fn kind_label(&self) -> &'static str {
+        match self.kind {
+            FnKind::Method | FnKind::TraitDefault => "Method",
+            FnKind::Free | FnKind::Nested => "Function",
+        }
+    }
+
+    pub fn location(&self, span: Span) -> Location {
+        Location::from_span(&self.file.path, span)
+    }
+
+    // Finding located at `span` and attributed to this function
+    pub fn finding(&self, rule_id: &str, severity: Severity, span: Span, message: String) -> Finding {
+        Finding::new(rule_id, severity, self.location(span), message)
+            .with_function(&self.name())
+            .with_module_path(&self.module_path)
+    }
+}
+
+// Every function body in the program: free functions, methods of inherent and
+// trait impls, trait default methods and fns nested in other bodies, in any module
+pub fn functions(program: &Program) -> Vec<FnItem<'_>> {
+    let mut functions = Vec::new();
+    for file in &program.files {
+        walk_items(file, &file.syntax_tree.items, &file.module_path, &mut functions);
+    }
+    functions
+}
+
+fn walk_items<'a>(file: &'a SourceFile, items: &'a [Item], module_path: &[String], out: &mut Vec<FnItem<'a>>) {
+    for item in items {
+        walk_item(file, item, module_path, FnKind::Free, out);
+    }
+}
+
+fn walk_item<'a>(file: &'a SourceFile, item: &'a Item, module_path: &[String], kind: FnKind, out: &mut Vec<FnItem<'a>>) {
+    match item {
+        Item::Fn(func) => {
+            out.push(FnItem {
+                file,
+                module_path: module_path.to_vec(),
+                owner: None,
+                kind,
+                attrs: &func.attrs,
+                sig: &func.sig,
+                block: &func.block,
+            });
+            walk_nested(file, &func.block, module_path, out);
+        }
+        Item::Impl(item_impl) => {
+            let owner = type_name(&item_impl.self_ty);
+            for impl_item in &item_impl.items {
+                if let ImplItem::Fn(method) = impl_item {
+                    out.push(FnItem {
+                        file,
+                        module_path: module_path.to_vec(),
+                        owner: owner.clone(),
+                        kind: FnKind::Method,
+                        attrs: &method.attrs,
+                        sig: &method.sig,
+                        block: &method.block,
+                    });
+                    walk_nested(file, &method.block, module_path, out);
+                }
+            }
+        }
+        Item::Trait(item_trait) => {
+            for trait_item in &item_trait.items {
+                if let TraitItem::Fn(method) = trait_item {
+                    if let Some(block) = &method.default {
+                        out.push(FnItem {
+                            file,
+                            module_path: module_path.to_vec(),
+                            owner: Some(item_trait.ident.to_string()),
+                            kind: FnKind::TraitDefault,
+                            attrs: &method.attrs,
+                            sig: &method.sig,
+                            block,
+                        });
+                        walk_nested(file, block, module_path, out);
+                    }
+                }
+            }
+        }
+        Item::Mod(item_mod) => {
+            if let Some((_, items)) = &item_mod.content {
+                let mut child_path = module_path.to_vec();
+                child_path.push(item_mod.ident.to_string());
+                walk_items(file, items, &child_path, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+// An item declared at module level, with the module it lives in
+pub struct ModuleItem<'a> {
+    pub file: &'a SourceFile,
+    pub module_path: Vec<String>,
+    pub item: &'a Item,
+}
+
+// Every module level item in the program, inline `mod` blocks included
+pub fn module_items(program: &Program) -> Vec<ModuleItem<'_>> {
+    let mut items = Vec::new();
+    for file in &program.files {
+        collect_module_items(file, &file.syntax_tree.items, &file.module_path, &mut items);
+    }
+    items
+}
+
+fn collect_module_items<'a>(file: &'a SourceFile, items: &'a [Item], module_path: &[String], out: &mut Vec<ModuleItem<'a>>) {
+    for item in items {
+        out.push(ModuleItem {
+            file,
+            module_path: module_path.to_vec(),
+            item,
+        });
+        if let Item::Mod(item_mod) = item {
+            if let Some((_, inline_items)) = &item_mod.content {
+                let mut child_path = module_path.to_vec();
+                child_path.push(item_mod.ident.to_string());
+                collect_module_items(file, inline_items, &child_path, out);
+            }
+        }
+    }
+}
+
+// Items declared inside a body. their own bodies are walked by `walk_item`
+// so the visitor stops at the first level of items it meets
+struct NestedItems<'a> {
+    items: Vec<&'a Item>,
+}
+
+impl<'a> Visit<'a> for NestedItems<'a> {
+    fn visit_item(&mut self, item: &'a Item) {
+        self.items.push(item);
+    }
+}
+
+fn walk_nested<'a>(file: &'a SourceFile, block: &'a Block, module_path: &[String], out: &mut Vec<FnItem<'a>>) {
+    let mut nested = NestedItems { items: Vec::new() };
+    visit::visit_block(&mut nested, block);
+    for item in nested.items {
+        walk_item(file, item, module_path, FnKind::Nested, out);
+    }
+}
+
+pub fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(type_path) => type_path.path.segments.last().map(|segment| segment.ident.to_string()),
+        Type::Reference(reference) => type_name(&reference.elem),
+        _ => None,
+    }
+}
+
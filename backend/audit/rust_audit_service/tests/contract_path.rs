@@ -0,0 +1,72 @@
+// `resolve_contract_path` must keep every audited path inside the contracts root
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use rust_audit_service::resolve_contract_path;
+
+// a fresh `root` with `contract.rs` in it and `secret.rs` next to it, outside the root
+fn sandbox(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust_audit_service_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("root/nested")).unwrap_or_else(|err| panic!("cannot create sandbox: {}", err));
+    fs::write(dir.join("root/contract.rs"), "fn main() {}").unwrap_or_else(|err| panic!("cannot write: {}", err));
+    fs::write(dir.join("secret.rs"), "fn secret() {}").unwrap_or_else(|err| panic!("cannot write: {}", err));
+    dir
+}
+
+fn assert_denied(result: io::Result<PathBuf>) {
+    match result {
+        Err(err) => assert_eq!(err.kind(), io::ErrorKind::PermissionDenied, "{}", err),
+        Ok(path) => panic!("{} was resolved outside of the root", path.display()),
+    }
+}
+
+#[test]
+fn paths_inside_the_root_resolve() {
+    let dir = sandbox("inside");
+    let root = dir.join("root");
+    let expected = root.join("contract.rs").canonicalize().unwrap();
+    assert_eq!(resolve_contract_path(&root, "contract.rs").unwrap(), expected);
+    assert_eq!(resolve_contract_path(&root, "nested/../contract.rs").unwrap(), expected);
+    assert_eq!(resolve_contract_path(&root, "./contract.rs").unwrap(), expected);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn parent_dir_escapes_are_denied() {
+    let dir = sandbox("parent");
+    let root = dir.join("root");
+    assert_denied(resolve_contract_path(&root, "../secret.rs"));
+    assert_denied(resolve_contract_path(&root, "nested/../../secret.rs"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn absolute_paths_outside_the_root_are_denied() {
+    let dir = sandbox("absolute");
+    let root = dir.join("root");
+    let secret = dir.join("secret.rs");
+    assert_denied(resolve_contract_path(&root, &secret.to_string_lossy()));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_files_are_not_found() {
+    let dir = sandbox("missing");
+    let result = resolve_contract_path(&dir.join("root"), "missing.rs");
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_leaving_the_root_are_denied() {
+    let dir = sandbox("symlink");
+    let root = dir.join("root");
+    std::os::unix::fs::symlink(dir.join("secret.rs"), root.join("link.rs")).unwrap();
+    std::os::unix::fs::symlink(&dir, root.join("up")).unwrap();
+    assert_denied(resolve_contract_path(&root, "link.rs"));
+    assert_denied(resolve_contract_path(&root, "up/secret.rs"));
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,358 @@
+// Runs every detector over the fixtures in the repository's `tests` directory and checks the
+// findings of the rules each fixture exercises, line by line
+use std::fs;
+use std::path::PathBuf;
+
+use rust_audit_service::analyze_source;
+use rust_audit_service::detectors::DetectorRegistry;
+use rust_audit_service::finding::Finding;
+
+fn analyze_fixture(name: &str) -> Vec<Finding> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../../tests").join(name);
+    let source = fs::read_to_string(&path).unwrap_or_else(|err| panic!("cannot read {}: {}", path.display(), err));
+    let registry = DetectorRegistry::default();
+    analyze_source(&source, name, &registry, |id| registry.is_enabled(id))
+        .unwrap_or_else(|err| panic!("cannot analyze {}: {}", name, err))
+}
+
+// the findings of `rules` must be exactly `expected`, as (rule, function, line)
+fn assert_findings(name: &str, rules: &[&str], expected: &[(&str, &str, usize)]) {
+    let mut actual: Vec<(&str, &str, usize)> = Vec::new();
+    let findings = analyze_fixture(name);
+    for finding in &findings {
+        if rules.contains(&finding.rule_id.as_str()) {
+            actual.push((&finding.rule_id, &finding.function_name, finding.location.start_line));
+        }
+    }
+    let mut expected = expected.to_vec();
+    actual.sort();
+    expected.sort();
+    assert_eq!(actual, expected, "findings of {:?} in {}", rules, name);
+}
+
+// functions the fixture marks as safe must not be reported by any rule
+fn assert_clean(name: &str, functions: &[&str]) {
+    let reported: Vec<String> = analyze_fixture(name)
+        .iter()
+        .filter(|finding| functions.contains(&finding.function_name.as_str()))
+        .map(|finding| format!("{} at line {}: {}", finding.rule_id, finding.location.start_line, finding.message))
+        .collect();
+    assert!(reported.is_empty(), "safe functions in {} were reported:\n{}", name, reported.join("\n"));
+}
+
+#[test]
+fn account_aliases() {
+    assert_findings(
+        "account_aliases.rs",
+        &["missing-signer-check", "missing-ownership-check", "unverified-lamport-debit"],
+        &[
+            ("missing-ownership-check", "check_wrong_account", 21),
+            ("missing-signer-check", "reassigned_after_check", 78),
+            ("unverified-lamport-debit", "slice_pattern_debit", 94),
+        ],
+    );
+    assert_clean("account_aliases.rs", &["check_through_alias", "destructured_signer"]);
+}
+
+#[test]
+fn account_close() {
+    assert_findings(
+        "account_close.rs",
+        &["unsafe-account-close", "missing-rent-exemption-check"],
+        &[
+            ("unsafe-account-close", "close_vault", 10),
+            ("unsafe-account-close", "close_with_stray_flag", 66),
+            ("unsafe-account-close", "close_to_program", 77),
+            // handing the account to the program is an assignment the rent rule looks at too
+            ("missing-rent-exemption-check", "close_to_program", 73),
+        ],
+    );
+    assert_clean("account_close.rs", &["close_vault_ok", "close_with_flag"]);
+}
+
+#[test]
+fn anchor_constraints() {
+    assert_findings(
+        "anchor_constraints.rs",
+        &["anchor-account-constraints"],
+        // the findings are on `#[derive(Accounts)]` structs, outside of any function
+        &[
+            ("anchor-account-constraints", "", 27),
+            ("anchor-account-constraints", "", 27),
+            ("anchor-account-constraints", "", 31),
+            ("anchor-account-constraints", "", 38),
+            ("anchor-account-constraints", "", 40),
+            ("anchor-account-constraints", "", 49),
+        ],
+    );
+}
+
+#[test]
+fn arbitrary_cpi() {
+    assert_findings(
+        "arbitrary_cpi.rs",
+        &["arbitrary-cpi"],
+        &[
+            ("arbitrary-cpi", "pay_out", 14),
+            ("arbitrary-cpi", "pay_out_signed", 21),
+            ("arbitrary-cpi", "pay_out_inverted", 66),
+            ("arbitrary-cpi", "pay_out_flagged", 78),
+            ("arbitrary-cpi", "pay_out_constant", 89),
+        ],
+    );
+}
+
+#[test]
+fn duplicate_accounts() {
+    assert_findings(
+        "duplicate_accounts.rs",
+        &["duplicate-mutable-accounts"],
+        &[
+            ("duplicate-mutable-accounts", "transfer_points", 14),
+            ("duplicate-mutable-accounts", "transfer_points_inverted", 52),
+        ],
+    );
+    assert_clean("duplicate_accounts.rs", &["copy_raw"]);
+}
+
+#[test]
+fn helper_summaries() {
+    assert_findings(
+        "helper_summaries.rs",
+        &["missing-signer-check", "missing-ownership-check", "missing-slippage-check", "taint-lamports"],
+        &[
+            ("missing-slippage-check", "settle", 58),
+            ("missing-signer-check", "withdraw_logged", 93),
+            ("taint-lamports", "withdraw_logged", 96),
+            ("missing-ownership-check", "read_config", 108),
+            ("missing-slippage-check", "swap_through_helper", 115),
+            ("missing-signer-check", "withdraw_lenient", 131),
+            ("missing-signer-check", "withdraw_verified", 145),
+        ],
+    );
+    assert_clean("helper_summaries.rs", &["withdraw"]);
+}
+
+#[test]
+fn lamport_debit() {
+    assert_findings(
+        "lamport_debit.rs",
+        &["unverified-lamport-debit"],
+        &[
+            ("unverified-lamport-debit", "withdraw", 12),
+            ("unverified-lamport-debit", "sweep", 22),
+            ("unverified-lamport-debit", "withdraw_inverted", 55),
+        ],
+    );
+}
+
+#[test]
+fn macro_checks() {
+    assert_findings(
+        "macro_checks.rs",
+        &["missing-signer-check", "missing-ownership-check", "unverified-lamport-debit", "taint-lamports", "unchecked-arithmetic", "panic-path"],
+        &[
+            ("missing-signer-check", "withdraw_logged", 40),
+            ("panic-path", "withdraw_logged", 41),
+            ("taint-lamports", "withdraw_logged", 43),
+            ("unchecked-arithmetic", "withdraw_logged", 43),
+            ("unchecked-arithmetic", "withdraw_logged", 44),
+        ],
+    );
+    assert_clean("macro_checks.rs", &["withdraw", "close_vault"]);
+}
+
+#[test]
+fn missing_signer() {
+    assert_findings(
+        "missing_signer.rs",
+        &["missing-signer-check"],
+        &[
+            ("missing-signer-check", "withdraw", 10),
+            ("missing-signer-check", "withdraw", 13),
+            ("missing-signer-check", "withdraw_wrong_binding", 38),
+            ("missing-signer-check", "burn_from", 46),
+            ("missing-signer-check", "mint_reward", 54),
+            ("missing-signer-check", "revoke_delegate", 61),
+            ("missing-signer-check", "withdraw_branches", 76),
+        ],
+    );
+}
+
+#[test]
+fn panic_paths() {
+    assert_findings(
+        "panic_paths.rs",
+        &["panic-path"],
+        &[
+            ("panic-path", "process_instruction", 7),
+            ("panic-path", "process_instruction", 12),
+            ("panic-path", "claim", 17),
+            ("panic-path", "claim", 18),
+            ("panic-path", "claim", 18),
+            ("panic-path", "claim", 20),
+            ("panic-path", "settle", 49),
+            ("panic-path", "settle", 54),
+            ("panic-path", "settle", 59),
+        ],
+    );
+}
+
+#[test]
+fn path_sensitive_checks() {
+    assert_findings(
+        "path_sensitive_checks.rs",
+        &["missing-ownership-check", "missing-slippage-check", "missing-access-control", "missing-signer-check", "unverified-lamport-debit"],
+        &[
+            ("missing-ownership-check", "logged_owner_check", 20),
+            ("missing-ownership-check", "check_after_use", 28),
+            ("missing-access-control", "branch_only_authority", 52),
+            ("missing-ownership-check", "branch_only_authority", 56),
+            ("missing-slippage-check", "swap_logged_slippage", 76),
+            ("missing-signer-check", "nested_signer_check", 101),
+            ("unverified-lamport-debit", "nested_owner_check", 116),
+        ],
+    );
+    assert_clean("path_sensitive_checks.rs", &["guarded_owner_check", "swap_checked_slippage"]);
+}
+
+#[test]
+fn pda_bump() {
+    assert_findings(
+        "pda_bump.rs",
+        &["unvalidated-pda"],
+        &[
+            ("unvalidated-pda", "withdraw", 9),
+            ("unvalidated-pda", "close", 21),
+            ("unvalidated-pda", "deposit", 44),
+            ("unvalidated-pda", "sweep", 67),
+            ("unvalidated-pda", "deposit_inverted", 78),
+            ("unvalidated-pda", "deposit_optional_check", 91),
+            ("unvalidated-pda", "sweep_other", 110),
+        ],
+    );
+    assert_clean("pda_bump.rs", &["pay_out", "deposit_ok"]);
+}
+
+#[test]
+fn precision_loss() {
+    assert_findings(
+        "precision_loss.rs",
+        &["precision-loss"],
+        &[
+            ("precision-loss", "deposit", 5),
+            ("precision-loss", "deposit", 6),
+            ("precision-loss", "withdraw", 11),
+            ("precision-loss", "withdraw", 12),
+            ("precision-loss", "withdraw", 12),
+        ],
+    );
+}
+
+#[test]
+fn reinitialization() {
+    assert_findings(
+        "reinitialization.rs",
+        &["account-reinitialization"],
+        &[
+            ("account-reinitialization", "initialize", 19),
+            ("account-reinitialization", "reset_vault", 31),
+            ("account-reinitialization", "initialize_inverted", 57),
+            ("account-reinitialization", "initialize_late_check", 67),
+            ("account-reinitialization", "initialize_other_created", 82),
+        ],
+    );
+}
+
+#[test]
+fn sysvar_spoofing() {
+    assert_findings(
+        "sysvar_spoofing.rs",
+        &["sysvar-spoofing"],
+        &[
+            ("sysvar-spoofing", "verify_signatures", 8),
+            ("sysvar-spoofing", "verify_signatures", 9),
+            ("sysvar-spoofing", "create_vault", 16),
+            ("sysvar-spoofing", "create_vault_wrong_id", 44),
+            ("sysvar-spoofing", "create_vault_late_check", 51),
+        ],
+    );
+}
+
+#[test]
+fn taint_flows() {
+    assert_findings(
+        "taint_flows.rs",
+        &["taint-lamports", "taint-invoke-program", "taint-pda-seeds", "taint-index", "taint-loop-bound"],
+        &[
+            ("taint-lamports", "pay", 40),
+            ("taint-lamports", "pay_fee_capped", 78),
+            ("taint-index", "forward", 87),
+            ("taint-invoke-program", "forward", 89),
+            ("taint-pda-seeds", "forward", 91),
+            ("taint-loop-bound", "distribute", 97),
+        ],
+    );
+    assert_clean("taint_flows.rs", &["pay_capped"]);
+}
+
+#[test]
+fn token_account() {
+    assert_findings(
+        "token_account.rs",
+        &["unvalidated-token-account"],
+        &[
+            ("unvalidated-token-account", "burn_tokens", 12),
+            ("unvalidated-token-account", "withdraw_tokens", 29),
+            ("unvalidated-token-account", "withdraw_tokens_inverted", 61),
+        ],
+    );
+}
+
+#[test]
+fn type_cosplay() {
+    assert_findings(
+        "type_cosplay.rs",
+        &["type-cosplay"],
+        &[
+            ("type-cosplay", "update_fee", 38),
+            ("type-cosplay", "update_tagged_late", 60),
+            ("type-cosplay", "update_fee_other_tag", 77),
+            ("type-cosplay", "read_user", 93),
+        ],
+    );
+}
+
+#[test]
+fn unbounded_loop() {
+    assert_findings(
+        "unbounded_loop.rs",
+        &["unbounded-loop"],
+        &[
+            ("unbounded-loop", "airdrop", 12),
+            ("unbounded-loop", "airdrop", 15),
+            ("unbounded-loop", "distribute", 37),
+            ("unbounded-loop", "walk_tree", 51),
+            ("unbounded-loop", "is_even", 57),
+            ("unbounded-loop", "is_odd", 61),
+            ("unbounded-loop", "airdrop_inverted", 70),
+            ("unbounded-loop", "airdrop_optional_cap", 83),
+        ],
+    );
+}
+
+#[test]
+fn unchecked_arithmetic() {
+    assert_findings(
+        "unchecked_arithmetic.rs",
+        &["unchecked-arithmetic"],
+        &[
+            ("unchecked-arithmetic", "withdraw", 9),
+            ("unchecked-arithmetic", "withdraw", 10),
+            ("unchecked-arithmetic", "withdraw", 11),
+            ("unchecked-arithmetic", "withdraw", 12),
+            ("unchecked-arithmetic", "withdraw_inverted", 40),
+        ],
+    );
+    assert_clean("unchecked_arithmetic.rs", &["withdraw_branch", "next_slot"]);
+}
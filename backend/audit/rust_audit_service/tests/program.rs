@@ -0,0 +1,154 @@
+// Loading a program crate from disk or from inline files: module resolution, `#[path]`
+// attributes and the crate boundary
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rust_audit_service::program::{LoadError, Program};
+
+fn sandbox(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust_audit_service_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap_or_else(|err| panic!("cannot create sandbox: {}", err));
+    dir
+}
+
+fn write(dir: &Path, path: &str, source: &str) {
+    let path = dir.join(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap_or_else(|err| panic!("cannot create {}: {}", parent.display(), err));
+    }
+    fs::write(&path, source).unwrap_or_else(|err| panic!("cannot write {}: {}", path.display(), err));
+}
+
+// (file, module path) of every loaded file, in load order
+fn modules(program: &Program) -> Vec<(String, String)> {
+    program
+        .files
+        .iter()
+        .map(|file| (file.path.clone(), file.module_path.join("::")))
+        .collect()
+}
+
+fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+    expected
+        .iter()
+        .map(|(path, module)| (path.to_string(), module.to_string()))
+        .collect()
+}
+
+#[test]
+fn follows_mod_declarations_and_path_attributes() {
+    let dir = sandbox("modules");
+    write(
+        &dir,
+        "src/lib.rs",
+        "mod state;\nmod instructions;\n#[path = \"generated/accounts.rs\"]\nmod accounts;\n\
+         mod handlers {\n    mod deposit;\n}\n",
+    );
+    write(&dir, "src/state.rs", "pub struct Vault;\n");
+    write(&dir, "src/instructions/mod.rs", "mod withdraw;\n");
+    write(&dir, "src/instructions/withdraw.rs", "pub fn withdraw() {}\n");
+    // children of a #[path] file are looked up next to it, like a mod.rs
+    write(&dir, "src/generated/accounts.rs", "mod keys;\n");
+    write(&dir, "src/generated/keys.rs", "pub fn keys() {}\n");
+    write(&dir, "src/handlers/deposit.rs", "pub fn deposit() {}\n");
+
+    let program = Program::load_crate(&dir).unwrap_or_else(|err| panic!("cannot load: {}", err));
+    assert_eq!(
+        modules(&program),
+        pairs(&[
+            ("src/lib.rs", ""),
+            ("src/state.rs", "state"),
+            ("src/instructions/mod.rs", "instructions"),
+            ("src/instructions/withdraw.rs", "instructions::withdraw"),
+            ("src/generated/accounts.rs", "accounts"),
+            ("src/generated/keys.rs", "accounts::keys"),
+            ("src/handlers/deposit.rs", "handlers::deposit"),
+        ])
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn accepts_the_manifest_path() {
+    let dir = sandbox("manifest");
+    write(&dir, "Cargo.toml", "[package]\nname = \"program\"\n");
+    write(&dir, "src/main.rs", "fn main() {}\n");
+    let program = Program::load_crate(&dir.join("Cargo.toml")).unwrap_or_else(|err| panic!("cannot load: {}", err));
+    assert_eq!(modules(&program), pairs(&[("src/main.rs", "")]));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn path_attributes_may_climb_within_the_crate_but_not_above_it() {
+    let dir = sandbox("climb");
+    write(
+        &dir,
+        "crate/src/lib.rs",
+        "#[path = \"../shared/util.rs\"]\nmod util;\n#[path = \"../../secret.rs\"]\nmod secret;\n",
+    );
+    write(&dir, "crate/shared/util.rs", "pub fn util() {}\n");
+    write(&dir, "secret.rs", "pub fn secret() {}\n");
+
+    let program = Program::load_crate(&dir.join("crate")).unwrap_or_else(|err| panic!("cannot load: {}", err));
+    assert_eq!(modules(&program), pairs(&[("src/lib.rs", ""), ("shared/util.rs", "util")]));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinked_modules_outside_the_crate_are_refused() {
+    let dir = sandbox("symlink");
+    write(&dir, "crate/src/lib.rs", "mod secret;\n");
+    write(&dir, "secret.rs", "pub fn secret() {}\n");
+    std::os::unix::fs::symlink(dir.join("secret.rs"), dir.join("crate/src/secret.rs")).unwrap();
+
+    match Program::load_crate(&dir.join("crate")) {
+        Err(LoadError::Io(path, err)) => {
+            assert_eq!(path, "src/secret.rs");
+            assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(program) => panic!("loaded {:?}", modules(&program)),
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reports_a_missing_crate_root_and_parse_errors() {
+    let dir = sandbox("errors");
+    assert!(matches!(Program::load_crate(&dir), Err(LoadError::NoCrateRoot)));
+
+    write(&dir, "src/lib.rs", "mod broken;\n");
+    write(&dir, "src/broken.rs", "fn broken( {}\n");
+    match Program::load_crate(&dir) {
+        Err(LoadError::Parse(path, _)) => assert_eq!(path, "src/broken.rs"),
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(program) => panic!("loaded {:?}", modules(&program)),
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn inline_files_are_normalized_and_cannot_escape() {
+    let mut files = BTreeMap::new();
+    files.insert(
+        "./src/lib.rs".to_string(),
+        "mod state;\nmod secret;\n#[path = \"../../outside.rs\"]\nmod outside;\n".to_string(),
+    );
+    files.insert("src/nested/../state.rs".to_string(), "pub struct Vault;\n".to_string());
+    // climbs above the crate root, so it is dropped and `mod secret` stays unresolved
+    files.insert("../src/secret.rs".to_string(), "pub fn secret() {}\n".to_string());
+    files.insert("/src/outside.rs".to_string(), "pub fn outside() {}\n".to_string());
+
+    let program = Program::from_files(&files).unwrap_or_else(|err| panic!("cannot load: {}", err));
+    assert_eq!(modules(&program), pairs(&[("src/lib.rs", ""), ("src/state.rs", "state")]));
+}
+
+#[test]
+fn inline_files_need_a_crate_root() {
+    let mut files = BTreeMap::new();
+    files.insert("../src/lib.rs".to_string(), "fn main() {}\n".to_string());
+    assert!(matches!(Program::from_files(&files), Err(LoadError::NoCrateRoot)));
+}
@@ -0,0 +1,126 @@
+// Enabling, disabling and selecting detectors through the registry
+use rust_audit_service::analyze_source;
+use rust_audit_service::detectors::{AnalysisContext, Detector, DetectorRegistry};
+use rust_audit_service::finding::{Finding, Location, Severity};
+
+// reports one finding under its own id on every program
+struct Fixed {
+    id: &'static str,
+    enabled_by_default: bool,
+}
+
+impl Detector for Fixed {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        "always reports"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Low
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        self.enabled_by_default
+    }
+
+    fn run(&self, _ctx: &AnalysisContext) -> Vec<Finding> {
+        let location = Location {
+            file: String::new(),
+            start_line: 1,
+            start_column: 1,
+            end_line: 1,
+            end_column: 1,
+        };
+        vec![Finding::new(self.id, Severity::Low, location, String::new())]
+    }
+}
+
+fn registry() -> DetectorRegistry {
+    let mut registry = DetectorRegistry::empty();
+    registry.register(Box::new(Fixed {
+        id: "always-on",
+        enabled_by_default: true,
+    }));
+    registry.register(Box::new(Fixed {
+        id: "opt-in",
+        enabled_by_default: false,
+    }));
+    registry
+}
+
+fn reported(registry: &DetectorRegistry) -> Vec<String> {
+    let mut ids: Vec<String> = analyze_source("fn main() {}", "lib.rs", registry, |id| registry.is_enabled(id))
+        .unwrap_or_else(|err| panic!("cannot analyze: {}", err))
+        .into_iter()
+        .map(|finding| finding.rule_id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn detectors_start_with_their_default() {
+    let registry = registry();
+    assert!(registry.contains("always-on"));
+    assert!(registry.contains("opt-in"));
+    assert!(!registry.contains("unknown"));
+    assert!(registry.is_enabled("always-on"));
+    assert!(!registry.is_enabled("opt-in"));
+
+    let listed: Vec<(&str, bool)> = registry.list().iter().map(|info| (info.id, info.enabled)).collect();
+    assert_eq!(listed, vec![("always-on", true), ("opt-in", false)]);
+    assert_eq!(reported(&registry), vec!["always-on"]);
+}
+
+#[test]
+fn enable_and_disable_change_what_runs() {
+    let mut registry = registry();
+    assert!(registry.enable("opt-in"));
+    assert!(registry.disable("always-on"));
+    assert!(registry.is_enabled("opt-in"));
+    assert!(!registry.is_enabled("always-on"));
+    assert_eq!(reported(&registry), vec!["opt-in"]);
+
+    assert!(registry.enable("always-on"));
+    assert_eq!(reported(&registry), vec!["always-on", "opt-in"]);
+}
+
+#[test]
+fn unknown_ids_are_refused() {
+    let mut registry = registry();
+    assert!(!registry.enable("unknown"));
+    assert!(!registry.disable("unknown"));
+    assert!(!registry.is_enabled("unknown"));
+    assert_eq!(registry.list().len(), 2);
+}
+
+#[test]
+fn selection_overrides_the_enabled_set() {
+    let registry = registry();
+    let findings = analyze_source("fn main() {}", "lib.rs", &registry, |id| id == "opt-in")
+        .unwrap_or_else(|err| panic!("cannot analyze: {}", err));
+    let ids: Vec<&str> = findings.iter().map(|finding| finding.rule_id.as_str()).collect();
+    assert_eq!(ids, vec!["opt-in"]);
+}
+
+#[test]
+#[should_panic(expected = "registered twice")]
+fn duplicate_ids_are_rejected() {
+    let mut registry = registry();
+    registry.register(Box::new(Fixed {
+        id: "opt-in",
+        enabled_by_default: true,
+    }));
+}
+
+#[test]
+fn default_registry_enables_the_builtin_rules() {
+    let registry = DetectorRegistry::default();
+    for id in ["missing-access-control", "missing-ownership-check", "missing-slippage-check"] {
+        assert!(registry.contains(id), "{} is not registered", id);
+        assert!(registry.is_enabled(id), "{} is not enabled", id);
+    }
+}
@@ -0,0 +1,85 @@
+// `walk::functions` must find every function body, wherever it is declared
+use rust_audit_service::finding::Severity;
+use rust_audit_service::program::Program;
+use rust_audit_service::walk::{functions, FnKind};
+
+const SOURCE: &str = r#"
+fn entry() {
+    fn helper() {}
+}
+
+pub mod my_program {
+    pub fn initialize() {}
+
+    mod inner {
+        fn deep() {}
+    }
+}
+
+struct Vault;
+
+impl Vault {
+    fn withdraw(&self) {
+        struct Local;
+        impl Local {
+            fn local() {}
+        }
+    }
+}
+
+trait Close {
+    fn close(&self) {}
+    fn required(&self);
+}
+
+impl Close for Vault {
+    fn required(&self) {}
+}
+"#;
+
+// (module path, owner, name, kind) of every function, sorted
+fn walked(source: &str) -> Vec<(String, Option<String>, String, FnKind)> {
+    let program = Program::from_source(source, "lib.rs").unwrap_or_else(|err| panic!("cannot parse: {}", err));
+    let mut walked: Vec<(String, Option<String>, String, FnKind)> = functions(&program)
+        .iter()
+        .map(|func| (func.module_path.join("::"), func.owner.clone(), func.name(), func.kind))
+        .collect();
+    walked.sort_by(|a, b| (&a.0, &a.2).cmp(&(&b.0, &b.2)));
+    walked
+}
+
+fn entry(module: &str, owner: Option<&str>, name: &str, kind: FnKind) -> (String, Option<String>, String, FnKind) {
+    (module.to_string(), owner.map(str::to_string), name.to_string(), kind)
+}
+
+#[test]
+fn finds_functions_in_every_position() {
+    assert_eq!(
+        walked(SOURCE),
+        vec![
+            entry("", Some("Close"), "close", FnKind::TraitDefault),
+            entry("", None, "entry", FnKind::Free),
+            entry("", None, "helper", FnKind::Nested),
+            entry("", Some("Local"), "local", FnKind::Method),
+            entry("", Some("Vault"), "required", FnKind::Method),
+            entry("", Some("Vault"), "withdraw", FnKind::Method),
+            entry("my_program", None, "initialize", FnKind::Free),
+            entry("my_program::inner", None, "deep", FnKind::Free),
+        ]
+    );
+}
+
+#[test]
+fn findings_carry_the_function_and_module() {
+    let program = Program::from_source(SOURCE, "lib.rs").unwrap_or_else(|err| panic!("cannot parse: {}", err));
+    let functions = functions(&program);
+    let Some(deep) = functions.iter().find(|func| func.name() == "deep") else {
+        panic!("deep was not walked");
+    };
+    let finding = deep.finding("rule", Severity::Low, deep.sig.ident.span(), String::new());
+    assert_eq!(finding.function_name, "deep");
+    assert_eq!(finding.module_path, "my_program::inner");
+    assert_eq!(finding.location.file, "lib.rs");
+    assert_eq!((finding.location.start_line, finding.location.start_column), (10, 12));
+    assert_eq!(deep.kind_label(), "Function");
+}
@@ -5,6 +5,22 @@ const async = require('async');
 const { performRustAudit } = require('./auditTools');
 const { saveAuditReport } = require('./mongo');
 
+// messages quote the uploaded source, so everything from the report is escaped before it goes into the page
+const escapeHtml = (value) => String(value ?? '')
+    .replace(/&/g, '&amp;')
+    .replace(/</g, '&lt;')
+    .replace(/>/g, '&gt;')
+    .replace(/"/g, '&quot;')
+    .replace(/'/g, '&#39;');
+
+const renderTrace = (trace) => {
+    if (!Array.isArray(trace) || trace.length === 0) {
+        return '';
+    }
+    const steps = trace.map(step => `<li>line ${escapeHtml(step.location && step.location.start_line)}: ${escapeHtml(step.note)}</li>`).join('');
+    return `<ol>${steps}</ol>`;
+};
+
 const auditContract = async (req, res) => {
     if (!req.file || req.file.length === 0) {
         return res.status(400).json({ error: 'Please upload a contract file.' });
@@ -14,22 +30,16 @@ const auditContract = async (req, res) => {
     const fileType = path.extname(req.file.originalname).toLowerCase(); 
     const auditReport = null;
 
-    const generatedFileName = req.file.filename;
-
-    const rustSharedVolumePath = '/usr/src/app/uploads';
-
-    const rustFilePath = path.join(rustSharedVolumePath, generatedFileName);
-
     try {
         const fileContent = await fs.promises.readFile(filePath, 'utf8');
 
         if (fileType === '.rs') {
-            const auditReport = await performRustAudit(rustFilePath);
+            const auditReport = await performRustAudit(fileContent, req.file.originalname);
 
             if (!auditReport || auditReport.length === 0) {
                 return res.status(200).send('<p style="color: green; font-weight: bold;">No vulnerabilities found.</p>');
             } else {
-                const reportHtml = auditReport.map(item => `<p style="color: green; font-weight: bold;">- ${item}</p>`).join('');
+                const reportHtml = auditReport.map(item => `<p style="color: green; font-weight: bold;">- [${escapeHtml(item.severity)}] ${escapeHtml(item.rule_id)}: ${escapeHtml(item.message)} (line ${escapeHtml(item.location.start_line)})</p>${renderTrace(item.trace)}`).join('');
                 return res.status(200).send(`${reportHtml}`);
             }
         } else {
@@ -37,7 +47,7 @@ const auditContract = async (req, res) => {
         }
     } catch (error) {
         console.error('Error processing file:', error);
-        return res.status(500).json({ error: 'Failed to process file' });
+        return res.status(500).json({ error: error.message || 'Failed to process file' });
     } finally {
 
         saveAuditReport (filePath, auditReport);
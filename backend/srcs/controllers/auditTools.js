@@ -12,14 +12,18 @@ const fileFilter = (req, file, cb) => {
     }
 };
 
-const performRustAudit = async (contractCode) => {
+const performRustAudit = async (contractCode, fileName) => {
     try {
         const response = await axios.post('http://rust-audit-service:8080/audit', {
-            contract_path: contractCode
+            source: contractCode,
+            file_name: fileName
         });
         return response.data.report;
     } catch (error) {
         console.error('Error auditing Rust contract:', error);
+        if (error.response && error.response.data && error.response.data.error) {
+            throw new Error(error.response.data.error);
+        }
         throw new Error('Rust audit service failed');
     }
 };
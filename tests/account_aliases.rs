@@ -0,0 +1,97 @@
+// account aliases: checks follow the account a binding holds through aliases, indexing, destructuring and reassignment rather than its name
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub balance: u64,
+}
+
+// the owner of `config` is checked but the data of `vault` is read
+pub fn check_wrong_account(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let config = next_account_info(account_info_iter)?;
+    let vault = next_account_info(account_info_iter)?;
+
+    if config.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let data = Vault::try_from_slice(&vault.data.borrow())?;
+    msg!("Balance: {}", data.balance);
+    Ok(())
+}
+
+// safe: `v` and `&accounts[2]` are the same account, whatever they are called
+pub fn check_through_alias(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    if accounts.len() < 3 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let v = &accounts[2];
+    let source = v;
+
+    if source.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let data = Vault::try_from_slice(&accounts[2].data.borrow())?;
+    msg!("Balance: {}", data.balance);
+    Ok(())
+}
+
+// safe: the tuple binds `payer` to the account that signed and `vault` to the one whose owner is checked
+pub fn destructured_signer(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let (payer, vault) = (next_account_info(account_info_iter)?, next_account_info(account_info_iter)?);
+
+    if vault.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut data = Vault::try_from_slice(&vault.data.borrow())?;
+    if data.authority != *payer.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    data.balance = amount;
+    Ok(())
+}
+
+// `authority` is checked and then rebound to the next account, which is used unchecked
+pub fn reassigned_after_check(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let mut authority = next_account_info(account_info_iter)?;
+
+    if vault_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    authority = next_account_info(account_info_iter)?;
+
+    let mut vault = Vault::try_from_slice(&vault_info.data.borrow())?;
+    if vault.authority != *authority.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    vault.balance -= amount;
+    vault.serialize(&mut &mut vault_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// the slice pattern binds `[payer, vault]` by position; `vault` loses lamports without an owner check
+pub fn slice_pattern_debit(_program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let [payer, vault, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    **vault.lamports.borrow_mut() -= amount;
+    **payer.lamports.borrow_mut() += amount;
+    Ok(())
+}
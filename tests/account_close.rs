@@ -0,0 +1,80 @@
+// unsafe account close: lamports drained to the destination while the data and owner stay in place
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, program_error::ProgramError, program_memory::sol_memset, pubkey::Pubkey, system_program};
+
+pub fn close_vault(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault = next_account_info(iter)?;
+    let destination = next_account_info(iter)?;
+    let dest_starting_lamports = destination.lamports();
+    **destination.lamports.borrow_mut() = dest_starting_lamports.checked_add(vault.lamports()).unwrap();
+    **vault.lamports.borrow_mut() = 0;
+    Ok(())
+}
+
+pub fn close_vault_ok(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault = next_account_info(iter)?;
+    let destination = next_account_info(iter)?;
+    if vault.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let dest_starting_lamports = destination.lamports();
+    **destination.lamports.borrow_mut() =
+        dest_starting_lamports.checked_add(vault.lamports()).ok_or(ProgramError::ArithmeticOverflow)?;
+    **vault.lamports.borrow_mut() = 0;
+    let len = vault.data_len();
+    sol_memset(&mut *vault.data.borrow_mut(), 0, len);
+    vault.assign(&system_program::ID);
+    Ok(())
+}
+
+pub fn close_with_discriminator(accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let destination = &accounts[1];
+    **destination.lamports.borrow_mut() += vault.lamports();
+    **vault.lamports.borrow_mut() -= vault.lamports();
+    vault.data.borrow_mut()[..8].copy_from_slice(&CLOSED_ACCOUNT_DISCRIMINATOR);
+    Ok(())
+}
+
+// safe: the closed flag is written to the vault's own state, which is stored back into the vault
+pub fn close_with_flag(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault = next_account_info(iter)?;
+    let destination = next_account_info(iter)?;
+    if vault.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !destination.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+    **destination.lamports.borrow_mut() =
+        destination.lamports().checked_add(vault.lamports()).ok_or(ProgramError::ArithmeticOverflow)?;
+    **vault.lamports.borrow_mut() = 0;
+    state.is_closed = true;
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// the flag lands on the destination's state, the vault keeps its data
+pub fn close_with_stray_flag(accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let destination = &accounts[1];
+    let mut receipt = Receipt::try_from_slice(&destination.data.borrow())?;
+    **destination.lamports.borrow_mut() += vault.lamports();
+    **vault.lamports.borrow_mut() -= vault.lamports();
+    receipt.is_closed = true;
+    receipt.serialize(&mut &mut destination.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// assigning the vault to the program keeps it alive with its data in place
+pub fn close_to_program(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let destination = &accounts[1];
+    **destination.lamports.borrow_mut() += vault.lamports();
+    **vault.lamports.borrow_mut() = 0;
+    vault.assign(program_id);
+    Ok(())
+}
@@ -0,0 +1,81 @@
+// anchor constraints: unchecked accounts without CHECK docs, mut accounts not tied to a signer, init_if_needed, seeds without bump and close to a non-signer
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod vault {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.balance += amount;
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.balance -= amount;
+        Ok(())
+    }
+
+    pub fn close_vault(_ctx: Context<CloseVault>) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(init_if_needed, payer = user, space = 8 + 40, seeds = [b"vault", user.key().as_ref()])]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub fee_receiver: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    pub authority: Signer<'info>,
+    /// CHECK: only receives lamports
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    #[account(mut, has_one = authority, close = receiver)]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+    /// CHECK: rent receiver
+    #[account(mut)]
+    pub receiver: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SafeWithdraw<'info> {
+    #[account(mut, has_one = authority, seeds = [b"vault", authority.key().as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut, close = authority, constraint = receipt.owner == authority.key() @ VaultError::Unauthorized)]
+    pub receipt: Box<Account<'info, Receipt>>,
+    pub authority: Signer<'info>,
+}
+
+#[account]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub balance: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Config {
+    pub fee_bps: u16,
+}
+
+#[account]
+pub struct Receipt {
+    pub owner: Pubkey,
+}
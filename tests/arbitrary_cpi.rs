@@ -0,0 +1,100 @@
+// arbitrary cpi: the token program is taken from the accounts and invoked without checking its id
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, instruction::{AccountMeta, Instruction}, program::{invoke, invoke_signed}, program_error::ProgramError, pubkey::Pubkey};
+
+pub fn pay_out(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let source = next_account_info(iter)?;
+    let destination = next_account_info(iter)?;
+    let token_program = next_account_info(iter)?;
+    let ix = Instruction {
+        program_id: *token_program.key,
+        accounts: vec![AccountMeta::new(*source.key, false), AccountMeta::new(*destination.key, false)],
+        data,
+    };
+    invoke(&ix, &[source.clone(), destination.clone(), token_program.clone()])?;
+    Ok(())
+}
+
+pub fn pay_out_signed(accounts: &[AccountInfo], data: Vec<u8>, seeds: &[&[u8]]) -> ProgramResult {
+    let vault = &accounts[0];
+    let target_program = &accounts[1];
+    invoke_signed(
+        &Instruction::new_with_bytes(*target_program.key, &data, vec![AccountMeta::new(*vault.key, true)]),
+        &[vault.clone(), target_program.clone()],
+        &[seeds],
+    )?;
+    Ok(())
+}
+
+pub fn pay_out_ok(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let source = next_account_info(iter)?;
+    let token_program = next_account_info(iter)?;
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let ix = Instruction {
+        program_id: *token_program.key,
+        accounts: vec![AccountMeta::new(*source.key, false)],
+        data,
+    };
+    invoke(&ix, &[source.clone(), token_program.clone()])?;
+    Ok(())
+}
+
+pub fn pay_out_builder(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let source = &accounts[0];
+    let destination = &accounts[1];
+    let authority = &accounts[2];
+    let token_program = &accounts[3];
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // spl_token rejects any program id other than its own
+    let ix = spl_token::instruction::transfer(token_program.key, source.key, destination.key, authority.key, &[], amount)?;
+    invoke(&ix, &[source.clone(), destination.clone(), authority.clone()])?;
+    Ok(())
+}
+
+pub fn pay_out_inverted(accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let source = &accounts[0];
+    let token_program = &accounts[1];
+    // backwards: only the real token program is turned away
+    if *token_program.key == spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    invoke(&Instruction::new_with_bytes(*token_program.key, &data, vec![AccountMeta::new(*source.key, false)]), &[source.clone()])?;
+    Ok(())
+}
+
+pub fn pay_out_flagged(accounts: &[AccountInfo], data: Vec<u8>, strict: bool) -> ProgramResult {
+    let source = &accounts[0];
+    let token_program = &accounts[1];
+    if strict {
+        if *token_program.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+    }
+    invoke(&Instruction::new_with_bytes(*token_program.key, &data, vec![AccountMeta::new(*source.key, false)]), &[source.clone()])?;
+    Ok(())
+}
+
+pub fn pay_out_constant(accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let source = &accounts[0];
+    let token_program = &accounts[1];
+    // MAX_FEE is not a program id
+    if *token_program.key == MAX_FEE {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    invoke(&Instruction::new_with_bytes(*token_program.key, &data, vec![AccountMeta::new(*source.key, false)]), &[source.clone()])?;
+    Ok(())
+}
+
+pub fn pay_out_nested_ok(accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let source = &accounts[0];
+    let token_program = &accounts[1];
+    if token_program.key.eq(&spl_token::ID) {
+        invoke(&Instruction::new_with_bytes(*token_program.key, &data, vec![AccountMeta::new(*source.key, false)]), &[source.clone()])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+// duplicate mutable accounts: the same account can be passed as both sides of the transfer and the balance is credited twice
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Balance {
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+pub fn transfer_points(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let from = next_account_info(iter)?;
+    let to = next_account_info(iter)?;
+    let mut from_state = Balance::try_from_slice(&from.data.borrow())?;
+    let mut to_state = Balance::try_from_slice(&to.data.borrow())?;
+    from_state.amount = from_state.amount.checked_sub(amount).ok_or(ProgramError::InsufficientFunds)?;
+    to_state.amount = to_state.amount.checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
+    from_state.serialize(&mut &mut from.data.borrow_mut()[..])?;
+    to_state.serialize(&mut &mut to.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn transfer_points_ok(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let from = &accounts[0];
+    let to = &accounts[1];
+    if from.key == to.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut from_state = Balance::try_from_slice(&from.data.borrow())?;
+    let mut to_state = Balance::try_from_slice(&to.data.borrow())?;
+    from_state.amount = from_state.amount.checked_sub(amount).ok_or(ProgramError::InsufficientFunds)?;
+    to_state.amount = to_state.amount.checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
+    from_state.serialize(&mut &mut from.data.borrow_mut()[..])?;
+    to_state.serialize(&mut &mut to.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// safe: neither account's data is read as a struct, so nothing says they hold the same type
+pub fn copy_raw(accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let source = next_account_info(iter)?;
+    let mirror = next_account_info(iter)?;
+    let log = next_account_info(iter)?;
+    mirror.data.borrow_mut().copy_from_slice(&source.data.borrow());
+    log.data.borrow_mut().fill(1);
+    Ok(())
+}
+
+pub fn transfer_points_inverted(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let from = &accounts[0];
+    let to = &accounts[1];
+    let mut from_state = Balance::try_from_slice(&from.data.borrow())?;
+    let mut to_state = Balance::try_from_slice(&to.data.borrow())?;
+    from_state.amount = from_state.amount.checked_sub(amount).ok_or(ProgramError::InsufficientFunds)?;
+    to_state.amount = to_state.amount.checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
+    from_state.serialize(&mut &mut from.data.borrow_mut()[..])?;
+    to_state.serialize(&mut &mut to.data.borrow_mut()[..])?;
+    // backwards and too late: distinct accounts are turned away once both are written
+    if from.key != to.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
+
+pub fn transfer_points_macro_ok(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let from = &accounts[0];
+    let to = &accounts[1];
+    require_keys_neq!(*from.key, *to.key, ErrorCode::SameAccount);
+    let mut from_state = Balance::try_from_slice(&from.data.borrow())?;
+    let mut to_state = Balance::try_from_slice(&to.data.borrow())?;
+    from_state.amount = from_state.amount.checked_sub(amount).ok_or(ProgramError::InsufficientFunds)?;
+    to_state.amount = to_state.amount.checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
+    from_state.serialize(&mut &mut from.data.borrow_mut()[..])?;
+    to_state.serialize(&mut &mut to.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn transfer_points_alias_ok(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let from = &accounts[0];
+    let to = &accounts[1];
+    let source = from;
+    if source.key.eq(to.key) {
+        return Err(ProgramError::InvalidArgument);
+    } else {
+        let mut from_state = Balance::try_from_slice(&from.data.borrow())?;
+        let mut to_state = Balance::try_from_slice(&to.data.borrow())?;
+        from_state.amount = from_state.amount.checked_sub(amount).ok_or(ProgramError::InsufficientFunds)?;
+        to_state.amount = to_state.amount.checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
+        from_state.serialize(&mut &mut from.data.borrow_mut()[..])?;
+        to_state.serialize(&mut &mut to.data.borrow_mut()[..])?;
+    }
+    Ok(())
+}
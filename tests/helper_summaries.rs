@@ -0,0 +1,149 @@
+// helper summaries: checks and sinks inside helper functions count at their call sites, whatever the helpers are called
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub balance: u64,
+}
+
+fn ensure_program_account(acc: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+    if acc.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+fn only_signed(acc: &AccountInfo) -> ProgramResult {
+    if !acc.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+// named like a check, but only logs
+fn assert_signer(acc: &AccountInfo) -> ProgramResult {
+    if !acc.is_signer {
+        msg!("{} did not sign", acc.key);
+    }
+    Ok(())
+}
+
+// goes through `ensure_program_account`, so callers get the owner check as well
+fn load_vault(vault_info: &AccountInfo, program_id: &Pubkey) -> Result<Vault, ProgramError> {
+    ensure_program_account(vault_info, program_id)?;
+    Ok(Vault::try_from_slice(&vault_info.data.borrow())?)
+}
+
+// only checks the signer when asked to, so callers cannot count on it
+fn signed_if(acc: &AccountInfo, strict: bool) -> ProgramResult {
+    if strict {
+        if !acc.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+    }
+    Ok(())
+}
+
+// shares its name with the `verify` method the handlers call on their accounts
+fn verify(acc: &AccountInfo) -> ProgramResult {
+    if !acc.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+fn settle(pool: &mut Pool, amount_in: u64) -> ProgramResult {
+    pool.swap(amount_in)?;
+    Ok(())
+}
+
+// safe: both account checks live in helpers
+pub fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    ensure_program_account(vault_info, program_id)?;
+    only_signed(authority)?;
+
+    let vault = Vault::try_from_slice(&vault_info.data.borrow())?;
+    if vault.authority != *authority.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if amount > vault_info.lamports() {
+        return Err(ProgramError::InsufficientFunds);
+    }
+    **vault_info.lamports.borrow_mut() -= amount;
+    **authority.lamports.borrow_mut() += amount;
+    Ok(())
+}
+
+// `assert_signer` never returns an error, so `authority` is used unchecked
+pub fn withdraw_logged(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    ensure_program_account(vault_info, program_id)?;
+    assert_signer(authority)?;
+
+    let vault = load_vault(vault_info, program_id)?;
+    if vault.authority != *authority.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    **vault_info.lamports.borrow_mut() -= amount;
+    **authority.lamports.borrow_mut() += amount;
+    Ok(())
+}
+
+// the owner check is made on the wrong account
+pub fn read_config(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let config = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+
+    ensure_program_account(config, program_id)?;
+    let vault = Vault::try_from_slice(&vault_info.data.borrow())?;
+    msg!("Balance: {}", vault.balance);
+    Ok(())
+}
+
+// the swap happens in `settle`, before any slippage check
+pub fn swap_through_helper(pool: &mut Pool, amount_in: u64, minimum_amount_out: u64) -> ProgramResult {
+    settle(pool, amount_in)?;
+    let amount_out = pool.last_output();
+    if amount_out < minimum_amount_out {
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
+
+// `signed_if` returns `Ok` without a check when `strict` is false
+pub fn withdraw_lenient(program_id: &Pubkey, accounts: &[AccountInfo], strict: bool) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    signed_if(authority, strict)?;
+    let vault = load_vault(vault_info, program_id)?;
+    if vault.authority != *authority.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+// `authority.verify()` is a method of some other type, not the free `verify` helper
+pub fn withdraw_verified(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    authority.verify()?;
+    let vault = load_vault(vault_info, program_id)?;
+    if vault.authority != *authority.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
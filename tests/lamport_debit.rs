@@ -0,0 +1,58 @@
+// unverified lamport debit: lamports moved out of accounts whose owner is never checked, signer and writable checks do not count
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey};
+
+pub fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+
+    if !vault.is_signer || !vault.is_writable {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    **vault.try_borrow_mut_lamports()? -= amount;
+    **recipient.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
+
+pub fn sweep(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let treasury = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+
+    **treasury.lamports.borrow_mut() = treasury.lamports().checked_sub(amount).ok_or(ProgramError::InsufficientFunds)?;
+    **recipient.lamports.borrow_mut() += amount;
+    Ok(())
+}
+
+pub fn withdraw_checked(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+
+    if vault.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !vault.is_writable {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    **vault.try_borrow_mut_lamports()? -= amount;
+    **recipient.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
+
+// the guards are inverted: they reject the vault the program owns and a signed authority
+pub fn withdraw_inverted(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+
+    if vault.owner == program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if vault.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    **vault.try_borrow_mut_lamports()? -= amount;
+    **recipient.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
@@ -0,0 +1,67 @@
+// macro checks: the arguments of require!, require_keys_eq!, assert!, assert_eq!, msg!, err! and error! are analyzed like plain code
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub balance: u64,
+}
+
+// safe: signer, owner, authority, balance and lamports are all checked inside macros
+pub fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    require!(authority.is_signer, ErrorCode::Unauthorized);
+    require_keys_eq!(*vault_info.owner, *program_id, ErrorCode::WrongOwner);
+
+    let mut vault = Vault::try_from_slice(&vault_info.data.borrow())?;
+    assert_eq!(vault.authority, *authority.key, "wrong authority");
+    require!(amount <= vault.balance, ErrorCode::InsufficientFunds);
+    require!(amount <= vault_info.lamports(), ErrorCode::InsufficientFunds);
+
+    vault.balance -= amount;
+    vault.serialize(&mut &mut vault_info.data.borrow_mut()[..])?;
+    **vault_info.lamports.borrow_mut() -= amount;
+    **authority.lamports.borrow_mut() += amount;
+    Ok(())
+}
+
+// `msg!` only logs whether `authority` signed, and panics without a third account
+pub fn withdraw_logged(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    assert!(vault_info.owner == program_id);
+    let vault = Vault::try_from_slice(&vault_info.data.borrow())?;
+    assert_eq!(vault.authority, *authority.key);
+    msg!("signed: {}, fee payer: {}", authority.is_signer, accounts.get(2).unwrap().key);
+
+    **vault_info.lamports.borrow_mut() -= amount;
+    **authority.lamports.borrow_mut() += amount;
+    Ok(())
+}
+
+// safe: both branches leave through `err!` and `error!`
+pub fn close_vault(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return err!(ErrorCode::Unauthorized);
+    }
+    if vault_info.owner != program_id {
+        return Err(error!(ErrorCode::WrongOwner));
+    }
+    let mut vault = Vault::try_from_slice(&vault_info.data.borrow())?;
+    if vault.authority != *authority.key {
+        return err!(ErrorCode::Unauthorized);
+    }
+    vault.balance = 0;
+    vault.serialize(&mut &mut vault_info.data.borrow_mut()[..])?;
+    Ok(())
+}
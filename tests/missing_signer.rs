@@ -0,0 +1,79 @@
+// missing signer check: authority compared against the stored key and payer used for a transfer, neither checked with is_signer
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, program::invoke, program_error::ProgramError, pubkey::Pubkey, system_instruction};
+
+pub fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault = next_account_info(iter)?;
+    let authority = next_account_info(iter)?;
+    let payer = next_account_info(iter)?;
+    let state = State::try_from_slice(&vault.data.borrow())?;
+    if state.authority != *authority.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    invoke(&system_instruction::transfer(payer.key, vault.key, 10), &[payer.clone()])?;
+    Ok(())
+}
+
+pub fn withdraw_ok(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault = next_account_info(iter)?;
+    let authority = next_account_info(iter)?;
+    if !authority.is_signer {
+        msg!("no");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let state = State::try_from_slice(&vault.data.borrow())?;
+    if state.authority != *authority.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+pub fn withdraw_wrong_binding(accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let authority = &accounts[1];
+    if !vault.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let ix = spl_token::instruction::transfer(&spl_token::id(), vault.key, vault.key, authority.key, &[], 5)?;
+    Ok(())
+}
+
+pub fn burn_from(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let source = &accounts[0];
+    let mint = &accounts[1];
+    let owner = &accounts[2];
+    let ix = spl_token::instruction::burn_checked(&spl_token::id(), source.key, mint.key, owner.key, &[], amount, 6)?;
+    Ok(())
+}
+
+pub fn mint_reward(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let mint = &accounts[0];
+    let destination = &accounts[1];
+    let mint_authority = &accounts[2];
+    let ix = spl_token::instruction::mint_to_checked(&spl_token::id(), mint.key, destination.key, mint_authority.key, &[], amount, 6)?;
+    Ok(())
+}
+
+pub fn revoke_delegate(accounts: &[AccountInfo]) -> ProgramResult {
+    let source = &accounts[0];
+    let owner = &accounts[1];
+    let ix = spl_token::instruction::revoke(&spl_token::id(), source.key, owner.key, &[])?;
+    Ok(())
+}
+
+pub fn withdraw_branches(accounts: &[AccountInfo], amount: u64, fast: bool) -> ProgramResult {
+    let vault = &accounts[0];
+    let authority = &accounts[1];
+    let destination = &accounts[2];
+    if fast {
+        // only this path checks the signature
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let ix = spl_token::instruction::transfer(&spl_token::id(), vault.key, destination.key, authority.key, &[], amount)?;
+    } else {
+        let ix = spl_token::instruction::transfer(&spl_token::id(), vault.key, destination.key, authority.key, &[], amount)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+// panic paths: unwrap, unchecked indexing, division by a stored total and panic! on the entrypoint's path
+use solana_program::{account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey};
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    match instruction_data[0] {
+        0 => claim(accounts, instruction_data),
+        1 => claim_ok(accounts, instruction_data),
+        2 => settle(accounts, instruction_data),
+        3 => settle_ok(accounts, instruction_data),
+        _ => panic!("unknown instruction"),
+    }
+}
+
+fn claim(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    let pool = &accounts[1];
+    let amount = u64::from_le_bytes(instruction_data[1..9].try_into().unwrap());
+    let state = Pool::try_from_slice(&pool.data.borrow())?;
+    let share = amount / state.total_shares;
+    Ok(())
+}
+
+fn claim_ok(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    let pool = accounts.get(1).ok_or(ProgramError::NotEnoughAccountKeys)?;
+    if instruction_data.len() < 9 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amount = u64::from_le_bytes(instruction_data[1..9].try_into().map_err(|_| ProgramError::InvalidInstructionData)?);
+    let state = Pool::try_from_slice(&pool.data.borrow())?;
+    if state.total_shares == 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let share = amount / state.total_shares;
+    Ok(())
+}
+
+// never called from the entrypoint
+fn debug_dump(accounts: &[AccountInfo]) {
+    let first = &accounts[0];
+    first.try_borrow_data().unwrap();
+}
+
+fn settle(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    // one account short for the index below
+    if accounts.len() < 2 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let fee_vault = &accounts[2];
+    // backwards: only a long enough buffer is turned away
+    if instruction_data.len() >= 9 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amount = u64::from_le_bytes(instruction_data[1..9].try_into().map_err(|_| ProgramError::InvalidInstructionData)?);
+    let state = Pool::try_from_slice(&fee_vault.data.borrow())?;
+    if state.total_shares != 0 {
+        msg!("pool has shares");
+    }
+    let share = amount / state.total_shares;
+    Ok(())
+}
+
+fn settle_ok(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    if accounts.len() <= 2 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let fee_vault = &accounts[2];
+    let mut i = 0;
+    while i < instruction_data.len() {
+        msg!("{}", instruction_data[i]);
+        i += 1;
+    }
+    let state = Pool::try_from_slice(&fee_vault.data.borrow())?;
+    if state.total_shares > 0 {
+        let share = state.balance / state.total_shares;
+    }
+    Ok(())
+}
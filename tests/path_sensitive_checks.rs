@@ -0,0 +1,119 @@
+// path sensitive checks: owner, slippage and authority checks only count when their failing branch leaves the function before the sensitive operation
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, msg, program::invoke, program_error::ProgramError, pubkey::Pubkey, system_instruction};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub balance: u64,
+}
+
+// the owner check only logs, deserialization still runs on a foreign account
+pub fn logged_owner_check(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+
+    if vault_info.owner != program_id {
+        msg!("Vault is not owned by this program");
+    }
+
+    let vault = Vault::try_from_slice(&vault_info.data.borrow())?;
+    msg!("Balance: {}", vault.balance);
+    Ok(())
+}
+
+// the check sits after the deserialization inside the loop, so the first iteration is unchecked
+pub fn check_after_use(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    for vault_info in accounts.iter() {
+        let vault = Vault::try_from_slice(&vault_info.data.borrow())?;
+        if vault_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        msg!("Balance: {}", vault.balance);
+    }
+    Ok(())
+}
+
+// safe: the early return dominates the deserialization
+pub fn guarded_owner_check(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+
+    if vault_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let vault = Vault::try_from_slice(&vault_info.data.borrow())?;
+    msg!("Balance: {}", vault.balance);
+    Ok(())
+}
+
+// the authority check only guards one branch, the other one still writes the vault
+pub fn branch_only_authority(_program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let mut vault = Vault::try_from_slice(&vault_info.data.borrow())?;
+
+    if amount > 0 {
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        vault.balance = amount;
+    } else {
+        vault.balance = 0;
+    }
+    vault.serialize(&mut &mut vault_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// the slippage comparison is made but a failure is only logged
+pub fn swap_logged_slippage(pool: &mut Pool, amount_in: u64, minimum_amount_out: u64) -> ProgramResult {
+    let expected_out = pool.quote(amount_in);
+    if expected_out < minimum_amount_out {
+        msg!("Slippage exceeded");
+    }
+    pool.swap(amount_in)?;
+    Ok(())
+}
+
+// safe: a `?` on the check leaves the function before the swap
+pub fn swap_checked_slippage(pool: &mut Pool, amount_in: u64, minimum_amount_out: u64) -> ProgramResult {
+    let expected_out = pool.quote(amount_in);
+    if expected_out < minimum_amount_out {
+        return Err(ProgramError::InvalidArgument);
+    }
+    pool.swap(amount_in)?;
+    Ok(())
+}
+
+// the signer check only runs for large amounts, the transfer runs for every amount
+pub fn nested_signer_check(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let destination = next_account_info(account_info_iter)?;
+
+    if amount > 5 {
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+    }
+    invoke(&system_instruction::transfer(authority.key, destination.key, amount), &[authority.clone(), destination.clone()])?;
+    Ok(())
+}
+
+// the owner check only runs for large amounts, the debit runs for every amount
+pub fn nested_owner_check(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+
+    if amount > 100 {
+        if vault.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+    }
+    **vault.try_borrow_mut_lamports()? -= amount;
+    **recipient.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
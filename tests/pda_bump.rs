@@ -0,0 +1,127 @@
+// pda validation: bump read from instruction data and account data, derived address used as an account but never compared, account with a stored bump never re-derived
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, instruction::Instruction, program::{invoke, invoke_signed}, program_error::ProgramError, pubkey::Pubkey, system_instruction};
+
+pub fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault = next_account_info(iter)?;
+    let user = next_account_info(iter)?;
+    let bump = instruction_data[0];
+    let pda = Pubkey::create_program_address(&[b"vault", user.key.as_ref(), &[bump]], program_id)?;
+    if pda != *vault.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(())
+}
+
+pub fn close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let user = &accounts[1];
+    let state = VaultState::try_from_slice(&vault.data.borrow())?;
+    let seeds = [b"vault".as_ref(), user.key.as_ref(), &[state.bump]];
+    let signer = Pubkey::create_program_address(&seeds, program_id)?;
+    msg!("closing {}", signer);
+    Ok(())
+}
+
+pub fn withdraw_ok(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let user = &accounts[1];
+    let (pda, bump) = Pubkey::find_program_address(&[b"vault", user.key.as_ref()], program_id);
+    if pda != *vault.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let check = Pubkey::create_program_address(&[b"vault", user.key.as_ref(), &[bump]], program_id)?;
+    if check != *vault.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(())
+}
+
+// the derived address becomes the destination of a transfer without being checked against `vault`
+pub fn deposit(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let user = &accounts[0];
+    let vault = &accounts[1];
+    let (vault_address, _) = Pubkey::find_program_address(&[b"vault", user.key.as_ref()], program_id);
+    invoke(&system_instruction::transfer(user.key, &vault_address, amount), &[user.clone(), vault.clone()])?;
+    Ok(())
+}
+
+// safe: the derivation only provides the bump for the signer seeds
+pub fn pay_out(program_id: &Pubkey, accounts: &[AccountInfo], instruction: &Instruction) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let user = next_account_info(iter)?;
+    let vault = next_account_info(iter)?;
+    let (_vault_address, bump) = Pubkey::find_program_address(&[b"vault", user.key.as_ref()], program_id);
+    invoke_signed(instruction, &[vault.clone(), user.clone()], &[&[b"vault", user.key.as_ref(), &[bump]]])?;
+    Ok(())
+}
+
+// `vault` keeps its bump, so it is a PDA of the program, but nothing re-derives its address
+pub fn sweep(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let user = &accounts[1];
+    if vault.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let state = VaultState::try_from_slice(&vault.data.borrow())?;
+    let bump = state.bump;
+    **user.lamports.borrow_mut() += vault.lamports();
+    **vault.lamports.borrow_mut() = 0;
+    Ok(())
+}
+
+// backwards: only the real vault is turned away before the transfer
+pub fn deposit_inverted(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let user = next_account_info(iter)?;
+    let vault = next_account_info(iter)?;
+    let (vault_address, _) = Pubkey::find_program_address(&[b"vault", user.key.as_ref()], program_id);
+    if vault_address == *vault.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    invoke(&system_instruction::transfer(user.key, &vault_address, amount), &[user.clone(), vault.clone()])?;
+    Ok(())
+}
+
+// the comparison only runs when the caller asks for it
+pub fn deposit_optional_check(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64, strict: bool) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let user = next_account_info(iter)?;
+    let vault = next_account_info(iter)?;
+    let (vault_address, _) = Pubkey::find_program_address(&[b"vault", user.key.as_ref()], program_id);
+    if strict && vault_address != *vault.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    invoke(&system_instruction::transfer(user.key, &vault_address, amount), &[user.clone(), vault.clone()])?;
+    Ok(())
+}
+
+// the escrow is re-derived, the vault whose bump is read is not
+pub fn sweep_other(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault = next_account_info(iter)?;
+    let escrow = next_account_info(iter)?;
+    let user = next_account_info(iter)?;
+    let (escrow_address, _) = Pubkey::find_program_address(&[b"escrow", user.key.as_ref()], program_id);
+    if escrow_address != *escrow.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let state = VaultState::try_from_slice(&vault.data.borrow())?;
+    let bump = state.bump;
+    Ok(())
+}
+
+pub fn deposit_ok(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let user = next_account_info(iter)?;
+    let vault = next_account_info(iter)?;
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (vault_address, _) = Pubkey::find_program_address(&[b"vault", user.key.as_ref()], program_id);
+    if vault_address != *vault.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    invoke(&system_instruction::transfer(user.key, &vault_address, amount), &[user.clone(), vault.clone()])?;
+    Ok(())
+}
@@ -0,0 +1,20 @@
+// precision loss: share price divided before it is scaled, withdraw burns rounded-down shares, fee truncated to u32
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey};
+
+pub fn deposit(accounts: &[AccountInfo], amount: u64, total_assets: u64, total_shares: u64) -> ProgramResult {
+    let shares_out = amount / total_assets * total_shares;
+    let mint_amount = (amount as u128 * total_shares as u128 + total_assets as u128 - 1) / total_assets as u128;
+    Ok(())
+}
+
+pub fn withdraw(accounts: &[AccountInfo], amount: u64, total_assets: u64, total_shares: u64) -> ProgramResult {
+    let shares_to_burn = amount.checked_mul(total_shares).unwrap() / total_assets;
+    let fee_amount = ((amount as u128) * 30 / 10_000) as u32;
+    Ok(())
+}
+
+pub fn withdraw_ok(accounts: &[AccountInfo], amount: u64, total_assets: u64, total_shares: u64) -> ProgramResult {
+    let shares_to_burn = (amount as u128 * total_shares as u128).div_ceil(total_assets as u128);
+    let payout = u64::try_from(shares_to_burn * total_assets as u128 / total_shares as u128).map_err(|_| ProgramError::InvalidArgument)?;
+    Ok(())
+}
@@ -0,0 +1,126 @@
+// reinitialization: initialize can be called again on a live vault and hands the authority to the caller
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, msg, program::invoke, program_error::ProgramError, pubkey::Pubkey, system_instruction};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Vault {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub balance: u64,
+}
+
+pub fn initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault = next_account_info(iter)?;
+    let authority = next_account_info(iter)?;
+    let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+    state.authority = *authority.key;
+    state.is_initialized = true;
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn reset_vault(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let authority = &accounts[1];
+    let state = Vault {
+        is_initialized: true,
+        authority: *authority.key,
+        balance: 0,
+    };
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn initialize_ok(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let authority = &accounts[1];
+    let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+    if state.is_initialized {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    state.authority = *authority.key;
+    state.is_initialized = true;
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn initialize_inverted(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let authority = &accounts[1];
+    let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+    // backwards: only a fresh vault is turned away
+    if !state.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    state.authority = *authority.key;
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn initialize_late_check(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let authority = &accounts[1];
+    let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+    state.authority = *authority.key;
+    state.is_initialized = true;
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    if state.is_initialized {
+        msg!("vault ready");
+    }
+    Ok(())
+}
+
+pub fn initialize_other_created(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let payer = &accounts[0];
+    let vault = &accounts[1];
+    let receipt = &accounts[2];
+    let authority = &accounts[3];
+    // creating the receipt does not make the vault fresh
+    invoke(&system_instruction::create_account(payer.key, receipt.key, 1_000_000, 8, program_id), &[payer.clone(), receipt.clone()])?;
+    let state = Vault { is_initialized: true, authority: *authority.key, balance: 0 };
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn initialize_created_ok(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let payer = &accounts[0];
+    let vault = &accounts[1];
+    let authority = &accounts[2];
+    invoke(&system_instruction::create_account(payer.key, vault.key, 1_000_000, 41, program_id), &[payer.clone(), vault.clone()])?;
+    let state = Vault { is_initialized: true, authority: *authority.key, balance: 0 };
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn initialize_empty_ok(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let authority = &accounts[1];
+    if !vault.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    let state = Vault { is_initialized: true, authority: *authority.key, balance: 0 };
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn initialize_tagged_ok(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let authority = &accounts[1];
+    let mut state = Tagged::try_from_slice(&vault.data.borrow())?;
+    if state.discriminator == VAULT_DISCRIMINATOR {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    state.discriminator = VAULT_DISCRIMINATOR;
+    state.authority = *authority.key;
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+const VAULT_DISCRIMINATOR: [u8; 8] = *b"vault___";
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Tagged {
+    pub discriminator: [u8; 8],
+    pub authority: Pubkey,
+}
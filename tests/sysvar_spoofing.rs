@@ -0,0 +1,66 @@
+// sysvar spoofing: the instructions sysvar and rent are read from whatever accounts the caller passed in
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey, rent::Rent, sysvar::{self, instructions::{load_current_index, load_instruction_at}, Sysvar}};
+
+pub fn verify_signatures(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let payer = next_account_info(iter)?;
+    let instruction_sysvar = next_account_info(iter)?;
+    let current = load_current_index(&instruction_sysvar.data.borrow());
+    let secp_ix = load_instruction_at((current - 1) as usize, &instruction_sysvar.data.borrow())?;
+    Ok(())
+}
+
+pub fn create_vault(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let rent_info = &accounts[1];
+    let rent = Rent::from_account_info(rent_info)?;
+    if !rent.is_exempt(vault.lamports(), vault.data_len()) {
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+    Ok(())
+}
+
+pub fn create_vault_ok(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let rent_info = &accounts[1];
+    if !sysvar::rent::check_id(rent_info.key) {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let rent = Rent::from_account_info(rent_info)?;
+    let clock = Clock::get()?;
+    if !rent.is_exempt(vault.lamports(), vault.data_len()) {
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+    Ok(())
+}
+
+pub fn create_vault_wrong_id(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let rent_info = &accounts[1];
+    // the clock id says nothing about the rent account
+    if !sysvar::clock::check_id(rent_info.key) {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let rent = Rent::from_account_info(rent_info)?;
+    Ok(())
+}
+
+pub fn create_vault_late_check(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let rent_info = &accounts[1];
+    let rent = Rent::from_account_info(rent_info)?;
+    if *rent_info.key != sysvar::rent::ID {
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
+
+pub fn create_vault_key_ok(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = &accounts[0];
+    let rent_info = &accounts[1];
+    if rent_info.key != &sysvar::rent::ID {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let rent = Rent::from_account_info(rent_info)?;
+    Ok(())
+}
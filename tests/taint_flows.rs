@@ -0,0 +1,111 @@
+// taint flows: instruction data followed through deserialization, arithmetic and helper calls into lamport debits, program ids, seeds, indices and loop bounds
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint, entrypoint::ProgramResult, instruction::Instruction, program::{invoke, invoke_signed}, program_error::ProgramError, pubkey::Pubkey};
+
+const MAX_RECIPIENTS: u8 = 16;
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Payout {
+    pub amount: u64,
+    pub fee: u64,
+    pub bump: u8,
+    pub slot: u8,
+    pub recipients: u8,
+    pub target: Pubkey,
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    let payout = Payout::try_from_slice(instruction_data)?;
+    match instruction_data[0] {
+        0 => pay(program_id, accounts, &payout),
+        1 => pay_capped(program_id, accounts, &payout),
+        2 => forward(accounts, &payout),
+        3 => pay_fee_capped(program_id, accounts, &payout),
+        _ => distribute(accounts, payout.recipients),
+    }
+}
+
+// the amount and fee come straight from the payload and nothing compares them with the balance
+fn pay(program_id: &Pubkey, accounts: &[AccountInfo], payout: &Payout) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+    if vault.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let total = payout.amount + payout.fee;
+    **vault.try_borrow_mut_lamports()? -= total;
+    **recipient.try_borrow_mut_lamports()? += total;
+    Ok(())
+}
+
+// safe: the amount is compared with the balance first and the slot is capped
+fn pay_capped(program_id: &Pubkey, accounts: &[AccountInfo], payout: &Payout) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+    if vault.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if payout.amount > vault.lamports() {
+        return Err(ProgramError::InsufficientFunds);
+    }
+    **vault.try_borrow_mut_lamports()? -= payout.amount;
+    **recipient.try_borrow_mut_lamports()? += payout.amount;
+
+    let slot = (payout.slot as usize).min(accounts.len() - 1);
+    if slot >= accounts.len() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let _ = &accounts[slot];
+    Ok(())
+}
+
+// only the fee is capped, the amount added to it still comes straight from the payload
+fn pay_fee_capped(program_id: &Pubkey, accounts: &[AccountInfo], payout: &Payout) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+    if vault.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let total = payout.amount + payout.fee.min(10);
+    **vault.try_borrow_mut_lamports()? -= total;
+    **recipient.try_borrow_mut_lamports()? += total;
+    Ok(())
+}
+
+// the program to call, the signer bump and the account slot are all picked by the caller
+fn forward(accounts: &[AccountInfo], payout: &Payout) -> ProgramResult {
+    let target = payout.target;
+    let bump = payout.bump;
+    let destination = &accounts[payout.slot as usize];
+
+    let ix = Instruction { program_id: target, accounts: vec![], data: vec![] };
+    invoke(&ix, &[destination.clone()])?;
+    invoke_signed(&ix, &[destination.clone()], &[&[b"vault", &[bump]]])?;
+    Ok(())
+}
+
+// `count` reaches the loop through a helper; only `distribute_capped` limits it
+fn distribute(accounts: &[AccountInfo], count: u8) -> ProgramResult {
+    for i in 0..count {
+        let _ = accounts.get(i as usize);
+    }
+    distribute_capped(accounts, count)
+}
+
+fn distribute_capped(accounts: &[AccountInfo], count: u8) -> ProgramResult {
+    if count > MAX_RECIPIENTS {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    for i in 0..count {
+        let _ = accounts.get(i as usize);
+    }
+    Ok(())
+}
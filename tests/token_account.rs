@@ -0,0 +1,87 @@
+// unvalidated token account: the source token account is unpacked and burned from without checking its mint or owner
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, program::invoke, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+use spl_token::state::Account as TokenAccount;
+
+pub fn burn_tokens(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let source_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    let source = TokenAccount::unpack(&source_info.data.borrow())?;
+    if source.amount < amount {
+        return Err(ProgramError::InsufficientFunds);
+    }
+    let ix = spl_token::instruction::burn(token_program.key, source_info.key, mint_info.key, authority_info.key, &[], amount)?;
+    invoke(&ix, &[source_info.clone(), mint_info.clone(), authority_info.clone()])?;
+    Ok(())
+}
+
+pub fn withdraw_tokens(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    let data = vault_info.try_borrow_data()?;
+    let vault = spl_token::state::Account::unpack(&data)?;
+    if vault.owner != *authority_info.key {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let ix = spl_token::instruction::transfer(token_program.key, vault_info.key, destination_info.key, authority_info.key, &[], amount)?;
+    invoke(&ix, &[vault_info.clone(), destination_info.clone(), authority_info.clone()])?;
+    Ok(())
+}
+
+pub fn withdraw_tokens_checked(program_id: &Pubkey, accounts: &[AccountInfo], expected_mint: Pubkey, amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    let vault = TokenAccount::unpack(&vault_info.data.borrow())?;
+    if vault.mint != expected_mint || vault.owner != *authority_info.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let ix = spl_token::instruction::transfer(token_program.key, vault_info.key, destination_info.key, authority_info.key, &[], amount)?;
+    invoke(&ix, &[vault_info.clone(), destination_info.clone(), authority_info.clone()])?;
+    Ok(())
+}
+
+pub fn withdraw_tokens_inverted(program_id: &Pubkey, accounts: &[AccountInfo], expected_mint: Pubkey, amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    let vault = TokenAccount::unpack(&vault_info.data.borrow())?;
+    // backwards: only the expected token account is turned away
+    if vault.mint == expected_mint && vault.owner == *authority_info.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let ix = spl_token::instruction::transfer(token_program.key, vault_info.key, destination_info.key, authority_info.key, &[], amount)?;
+    invoke(&ix, &[vault_info.clone(), destination_info.clone(), authority_info.clone()])?;
+    Ok(())
+}
+
+pub fn withdraw_tokens_nested(program_id: &Pubkey, accounts: &[AccountInfo], expected_mint: Pubkey, amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    let vault = TokenAccount::unpack(&vault_info.data.borrow())?;
+    if vault.mint == expected_mint {
+        if vault.owner != *authority_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let ix = spl_token::instruction::transfer(token_program.key, vault_info.key, destination_info.key, authority_info.key, &[], amount)?;
+        invoke(&ix, &[vault_info.clone(), destination_info.clone(), authority_info.clone()])?;
+    }
+    Ok(())
+}
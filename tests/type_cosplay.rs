@@ -0,0 +1,102 @@
+// type cosplay: User and Admin serialize to the same bytes and the handler never checks which one it got
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct User {
+    pub authority: Pubkey,
+    pub balance: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Admin {
+    pub authority: Pubkey,
+    pub fee_bps: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct TaggedConfig {
+    pub discriminator: [u8; 8],
+    pub authority: Pubkey,
+    pub fee_bps: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct TaggedVault {
+    pub discriminator: [u8; 8],
+    pub authority: Pubkey,
+    pub balance: u64,
+}
+
+pub fn update_fee(program_id: &Pubkey, accounts: &[AccountInfo], fee_bps: u64) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let admin_account = next_account_info(iter)?;
+    let signer = next_account_info(iter)?;
+    if admin_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let mut admin = Admin::try_from_slice(&admin_account.data.borrow())?;
+    if admin.authority != *signer.key || !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    admin.fee_bps = fee_bps;
+    admin.serialize(&mut &mut admin_account.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn update_tagged(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let config_account = &accounts[0];
+    let data = config_account.data.borrow();
+    let config: TaggedConfig = BorshDeserialize::deserialize(&mut &data[..])?;
+    if config.discriminator != CONFIG_DISCRIMINATOR {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+// the tag is only compared after the fee has already been written back
+pub fn update_tagged_late(program_id: &Pubkey, accounts: &[AccountInfo], fee_bps: u64) -> ProgramResult {
+    let config_account = &accounts[0];
+    let mut config = TaggedConfig::try_from_slice(&config_account.data.borrow())?;
+    config.fee_bps = fee_bps;
+    config.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+    if config.discriminator != CONFIG_DISCRIMINATOR {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+// the tag checked belongs to another account's state
+pub fn update_fee_other_tag(program_id: &Pubkey, accounts: &[AccountInfo], fee_bps: u64) -> ProgramResult {
+    let admin_account = &accounts[0];
+    let config_account = &accounts[1];
+    let config = TaggedConfig::try_from_slice(&config_account.data.borrow())?;
+    if config.discriminator != CONFIG_DISCRIMINATOR {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let mut admin = Admin::try_from_slice(&admin_account.data.borrow())?;
+    admin.fee_bps = fee_bps;
+    admin.serialize(&mut &mut admin_account.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Ledger {
+    pub authority: Pubkey,
+    pub entries: u64,
+    pub frozen: bool,
+}
+
+// `deserialize` stops after the user's fields, so a ledger or an admin reads as a user
+pub fn read_user(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let user_account = &accounts[0];
+    let user = User::deserialize(&mut &user_account.data.borrow()[..])?;
+    Ok(())
+}
+
+// safe as far as the layout goes: `try_from_slice` rejects the shorter user and admin accounts
+pub fn read_ledger(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ledger_account = &accounts[0];
+    let ledger = Ledger::try_from_slice(&ledger_account.data.borrow())?;
+    Ok(())
+}
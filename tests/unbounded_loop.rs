@@ -0,0 +1,97 @@
+// unbounded loops: iteration counts taken from instruction data, remaining_accounts or a stored vector without a cap, and recursion
+use anchor_lang::prelude::*;
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey};
+
+const MAX_RECIPIENTS: u64 = 16;
+
+pub fn airdrop(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    let count = u64::from_le_bytes(instruction_data[..8].try_into().unwrap());
+    let account_info_iter = &mut accounts.iter();
+    let pool = next_account_info(account_info_iter)?;
+    let mut state = Pool::try_from_slice(&pool.data.borrow())?;
+    for i in 0..count {
+        state.total += 1;
+    }
+    for member in state.members.iter() {
+        msg!("member {}", member);
+    }
+    Ok(())
+}
+
+pub fn airdrop_capped(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    let count = u64::from_le_bytes(instruction_data[..8].try_into().unwrap());
+    if count > MAX_RECIPIENTS {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let mut i = 0;
+    while i < count {
+        i += 1;
+    }
+    for byte in instruction_data {
+        msg!("{}", byte);
+    }
+    Ok(())
+}
+
+pub fn distribute(ctx: Context<Distribute>, amounts: Vec<u64>) -> Result<()> {
+    for account in ctx.remaining_accounts.iter() {
+        msg!("{}", account.key);
+    }
+    for amount in amounts.iter().take(8) {
+        msg!("{}", amount);
+    }
+    let depth = walk_tree(&ctx.accounts.tree.root);
+    let even = is_even(depth);
+    msg!("even depth: {}", even);
+    Ok(())
+}
+
+fn walk_tree(node: &Node) -> u64 {
+    match &node.child {
+        Some(child) => 1 + walk_tree(child),
+        None => 1,
+    }
+}
+
+fn is_even(n: u64) -> bool {
+    if n == 0 { true } else { is_odd(n - 1) }
+}
+
+fn is_odd(n: u64) -> bool {
+    if n == 0 { false } else { is_even(n - 1) }
+}
+
+pub fn airdrop_inverted(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    let count = u64::from_le_bytes(instruction_data[..8].try_into().unwrap());
+    // backwards: only small batches are turned away
+    if count < MAX_RECIPIENTS {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    for i in 0..count {
+        msg!("{}", i);
+    }
+    Ok(())
+}
+
+pub fn airdrop_optional_cap(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8], strict: bool) -> ProgramResult {
+    let count = u64::from_le_bytes(instruction_data[..8].try_into().unwrap());
+    if strict {
+        if count > MAX_RECIPIENTS {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+    }
+    for i in 0..count {
+        msg!("{}", i);
+    }
+    Ok(())
+}
+
+pub fn airdrop_nested_ok(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    let count = u64::from_le_bytes(instruction_data[..8].try_into().unwrap());
+    if count <= MAX_RECIPIENTS {
+        for i in 0..count {
+            msg!("{}", i);
+        }
+    }
+    Ok(())
+}
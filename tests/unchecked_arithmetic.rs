@@ -0,0 +1,62 @@
+// unchecked arithmetic: lamports, stored balances and instruction amounts added and subtracted without checked_* or a bound
+use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey};
+
+pub fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault = next_account_info(iter)?;
+    let user = next_account_info(iter)?;
+    let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+    state.balance -= amount;
+    **vault.lamports.borrow_mut() -= amount;
+    **user.lamports.borrow_mut() += amount;
+    let fee = amount * 3 / 1000;
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn withdraw_ok(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let vault = &accounts[0];
+    let user = &accounts[1];
+    let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+    if state.balance < amount {
+        return Err(ProgramError::InsufficientFunds);
+    }
+    state.balance -= amount;
+    let vault_lamports = vault.lamports().checked_sub(amount).ok_or(ProgramError::InsufficientFunds)?;
+    **vault.lamports.borrow_mut() = vault_lamports;
+    **user.lamports.borrow_mut() = user.lamports().saturating_add(amount);
+    let fee = (amount as u128) * 3 / 1000;
+    let index = 1 + 2;
+    Ok(())
+}
+
+// the guard rejects the safe case and lets `balance < amount` through
+pub fn withdraw_inverted(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let vault = &accounts[0];
+    let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+    if state.balance > amount {
+        return Err(ProgramError::InvalidArgument);
+    }
+    state.balance -= amount;
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// safe: the subtraction only runs inside the branch that checked the balance
+pub fn withdraw_branch(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let vault = next_account_info(&mut accounts.iter())?;
+    if vault.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+    if state.balance >= amount {
+        state.balance -= amount;
+    }
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// safe: `step` is a helper parameter, not an instruction argument
+fn next_slot(slot: u64, step: u64) -> u64 {
+    slot + step
+}